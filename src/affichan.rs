@@ -1,13 +1,19 @@
 //! Module contenant la structure [`Affichan`].
 
 use errors::Error;
-use serenity::all::Message;
-use serenity::all::{ChannelId, MessageId, UserId};
+use serenity::all::{Message, MessageFlags, MessageUpdateEvent};
+use serenity::all::{ChannelId, GuildChannel, MessageId, UserId};
 use serenity::all::{Context as SerenityContext, Context};
-use serenity::futures::future::{join_all, try_join_all};
-use std::collections::HashMap;
+use serenity::futures::stream::{self, StreamExt, TryStreamExt};
+use std::collections::hash_map::DefaultHasher;
+use std::collections::{HashMap, HashSet};
+use std::future::Future;
+use std::hash::{Hash, Hasher};
 use std::mem::take;
+use std::time::Duration;
+use tokio::time;
 use tools::PreloadedChannel;
+use tracing::{debug, error, info, warn};
 use yaml_rust2::{yaml, Yaml};
 
 use crate::tools::Preloaded;
@@ -36,7 +42,97 @@ pub struct Affichan<T: Object> {
     /// Attention : l’objet est fourni en tant que [`Option`] étant donné que l’existence
     /// de l’objet n’est pas assurée lors de l’utilisation de ces tests. Il convient à l’utilisateur
     /// de cette librairie de prendre en compte le cas où celle-ci serait [`None`].
-    test: Box<dyn Fn(Option<&T>) -> bool + Sync + Send + 'static>
+    test: Box<dyn Fn(Option<&T>) -> bool + Sync + Send + 'static>,
+    /// Objets forcés dans le salon d’affichage, qu’ils passent le test ou non.
+    forced_in: HashSet<u64>,
+    /// Objets forcés hors du salon d’affichage, qu’ils passent le test ou non.
+    forced_out: HashSet<u64>,
+    /* Identifiants des objets de la base de données lors de la dernière mise à jour complète.
+     * None si l’affichan doit être entièrement recalculé à la prochaine mise à jour. */
+    known_ids: Option<HashSet<u64>>,
+    /* Empreinte du dernier contenu envoyé pour chaque objet (voir _empreinte), permettant de ne
+     * pas éditer un message dont le contenu n’a pas changé. Non sauvegardé : après un
+     * redémarrage, la première édition de chaque message est toujours envoyée. */
+    embed_hashes: HashMap<u64, u64>,
+    /* Objets dont le message sauvegardé n’a pas été retrouvé au chargement, à republier à la
+     * prochaine mise à jour. */
+    to_recreate: HashSet<u64>,
+    /* Nombre maximal d’appels simultanés à l’API Discord, défini par Bot::api_concurrency */
+    api_concurrency: usize,
+    /* Journalisation des messages édités, supprimés, créés et intacts à chaque mise à jour,
+     * définie par Bot::debug_affichans */
+    debug: bool
+}
+
+/* Renvoie true si l’objet doit appartenir au salon d’affichage, d’après le test et les objets forcés.
+ * Les champs sont passés séparément pour permettre l’emprunt mutable des messages en parallèle. */
+fn _accepte<T: Object>(
+    test: &(dyn Fn(Option<&T>) -> bool + Sync + Send),
+    forced_in: &HashSet<u64>,
+    forced_out: &HashSet<u64>,
+    database: &HashMap<u64, T>,
+    object_id: &u64
+) -> bool {
+    !forced_out.contains(object_id) && database.contains_key(object_id)
+        && (forced_in.contains(object_id) || test(database.get(object_id)))
+}
+
+/* Empreinte du contenu du message de l’objet, tel que produit par Object::get_message_edit.
+ * EditMessage n’implémentant pas Hash, l’empreinte est calculée sur sa représentation Debug. */
+fn _empreinte<T: Object>(object: &T) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    format!("{:?}", object.get_message_edit()).hash(&mut hasher);
+    hasher.finish()
+}
+
+/* Exécute les futures données en limitant le nombre d’appels simultanés à l’API Discord (voir
+ * Bot::api_concurrency). Les résultats sont renvoyés dans l’ordre des futures.
+ * Les futures sont d’abord collectées : garder les itérateurs (et leurs closures) dans le stream
+ * empêche le compilateur de prouver que la future obtenue est Send. */
+async fn _join_limited<F: Future>(futures: impl IntoIterator<Item = F>, limit: usize) -> Vec<F::Output> {
+    let futures: Vec<F> = futures.into_iter().collect();
+    stream::iter(futures).buffered(limit.max(1)).collect().await
+}
+
+/* Comme _join_limited, mais s’arrête à la première erreur et la renvoie, comme try_join_all. */
+async fn _try_join_limited<O, E, F: Future<Output = Result<O, E>>>(futures: impl IntoIterator<Item = F>, limit: usize) -> Result<Vec<O>, E> {
+    let futures: Vec<F> = futures.into_iter().collect();
+    stream::iter(futures).buffered(limit.max(1)).try_collect().await
+}
+
+/* Nombre de nouvelles tentatives de récupération d’un message après une erreur transitoire */
+const FETCH_RETRIES: u32 = 3;
+
+/* Récupère un message du salon. Renvoie None si Discord indique que le message n’existe pas ou
+ * n’est pas accessible (erreur 4xx autre que 429). Les autres erreurs (réseau, erreurs serveur)
+ * pouvant être transitoires, la récupération est retentée FETCH_RETRIES fois avec un délai
+ * doublant à chaque tentative, avant de renvoyer l’erreur. */
+async fn _fetch_message(chan: &GuildChannel, ctx: &SerenityContext, message_id: MessageId) -> Result<Option<Message>, ErrType> {
+    let mut delai = Duration::from_secs(1);
+    let mut tentative = 0;
+    loop {
+        match chan.message(ctx, message_id).await {
+            Ok(message) => return Ok(Some(message)),
+            Err(serenity::Error::Http(e)) if e.status_code()
+                .is_some_and(|status| status.is_client_error() && status.as_u16() != 429) => return Ok(None),
+            Err(e) if tentative < FETCH_RETRIES => {
+                debug!("Échec de la récupération du message {message_id} ({e}), nouvelle tentative dans {} s.", delai.as_secs());
+                time::sleep(delai).await;
+                delai *= 2;
+                tentative += 1;
+            },
+            Err(e) => return Err(e.into())
+        }
+    }
+}
+
+/* Publie le message de l’objet dans le salon et y ajoute les réactions de Object::get_reactions. */
+async fn _publish<T: Object>(chan: &GuildChannel, ctx: &SerenityContext, object: &T) -> Result<Message, ErrType> {
+    let message = chan.send_message(ctx, object.get_message()).await?;
+    for reaction in object.get_reactions() {
+        message.react(ctx, reaction).await?;
+    }
+    Ok(message)
 }
 
 impl<T: Object> Affichan<T> {
@@ -49,7 +145,14 @@ impl<T: Object> Affichan<T> {
                 if let Some(ecrit) = ecrit {
                     test(ecrit)
                 } else {false}
-            })
+            }),
+            forced_in: HashSet::new(),
+            forced_out: HashSet::new(),
+            known_ids: None,
+            embed_hashes: HashMap::new(),
+            to_recreate: HashSet::new(),
+            api_concurrency: 4,
+            debug: false
         }
     }
 
@@ -62,19 +165,34 @@ impl<T: Object> Affichan<T> {
     /// Sauvegarde les Affichans dans un objet YAML.
     ///
     /// Cette fonction est appelée automatiquement dans [`Bot::save`] pour tous les Affichans du bot.
+    /// La sauvegarde contient les messages ainsi que les objets forcés dans ou hors de l’affichan
+    /// (voir [`Affichan::force_in`] et [`Affichan::force_out`]).
     pub fn save(&self) -> Yaml {
-        Yaml::Array(self.messages.iter().map(|(&object_id, message)| {
+        let ids_to_yaml = |ids: &HashSet<u64>| Yaml::Array(ids.iter().map(|&id| Yaml::Integer(id as i64)).collect());
+        let mut out = yaml::Hash::new();
+        out.insert(Yaml::String("messages".to_string()), Yaml::Array(self.messages.iter().map(|(&object_id, message)| {
             let mut out = yaml::Hash::new();
             out.insert(Yaml::String("id".to_string()), Yaml::Integer(object_id as i64));
             out.insert(Yaml::String("message_id".to_string()), Yaml::Integer(message.id.get() as i64));
             Yaml::Hash(out)
-        }).collect())
+        }).collect()));
+        out.insert(Yaml::String("forced_in".to_string()), ids_to_yaml(&self.forced_in));
+        out.insert(Yaml::String("forced_out".to_string()), ids_to_yaml(&self.forced_out));
+        Yaml::Hash(out)
     }
 
-    /* Charge une sauvegarde d’Affichan. Fonction utilisée dans init. */
-    async fn _load_from_save(&self, saved_data: &Yaml, ctx: &SerenityContext) -> Result<HashMap<u64, Message>, ErrType> {
-        println!("Chargement à partir d'une sauvegarde d'affichan…");
-        Ok(try_join_all(saved_data.as_vec().ok_or(ErrType::YamlParseError("Erreur de yaml dans les affichans: pas un tableau.".to_string()))?
+    /* Charge les objets forcés depuis une sauvegarde d’Affichan. Fonction utilisée dans init. */
+    fn _load_forced(saved_data: &Yaml) -> HashSet<u64> {
+        saved_data.as_vec().map_or_else(HashSet::new, |ids| ids.iter()
+            .filter_map(|id| id.as_i64())
+            .map(|id| id as u64).collect())
+    }
+
+    /* Charge une sauvegarde d’Affichan. Les objets dont le message n’a pas été retrouvé sont
+     * ajoutés à to_recreate. Fonction utilisée dans init. */
+    async fn _load_from_save(&mut self, saved_data: &Yaml, ctx: &SerenityContext) -> Result<HashMap<u64, Message>, ErrType> {
+        info!("Chargement à partir d'une sauvegarde d'affichan…");
+        let chargement = _try_join_limited(saved_data.as_vec().ok_or(ErrType::YamlParseError("Erreur de yaml dans les affichans: pas un tableau.".to_string()))?
             .into_iter().map(|yaml_message| async { match yaml_message.as_hash() {
             Some(_) => {
                 let object_id = yaml_message["id"].as_i64();
@@ -82,45 +200,66 @@ impl<T: Object> Affichan<T> {
                 if object_id.is_none() || message_id.is_none() {
                     Err(ErrType::YamlParseError("Erreur de yaml dans un affichan: un identifiant n’est pas un entier.".into()))
                 } else {
+                    let object_id = object_id.unwrap() as u64;
                     let message_id = message_id.unwrap() as u64;
-                    println!("Récupération du message {message_id}…");
-                    match self.chan.get().unwrap().message(ctx, MessageId::new(message_id)).await {
-                        Ok(message) => Ok(Some((object_id.unwrap() as u64, message))),
-                        Err(_) => {eprintln!("Message {message_id} non trouvé sur Discord. Tant pis."); Ok(None)}
+                    debug!("Récupération du message {message_id}…");
+                    match _fetch_message(self.chan.get()?, ctx, MessageId::new(message_id)).await {
+                        Ok(Some(message)) => Ok((object_id, Some(message))),
+                        Ok(None) => {
+                            warn!("Message {message_id} de l’objet {object_id} non trouvé sur Discord : il sera recréé.");
+                            Ok((object_id, None))
+                        },
+                        Err(e) => {
+                            error!("Impossible de récupérer le message {message_id} de l’objet {object_id} : {e}. \
+                                Il sera recréé et l’ancien message pourrait apparaître en double.");
+                            Ok((object_id, None))
+                        }
                     }
                 }
             },
             None => Err(ErrType::YamlParseError("Erreur de yaml dans un affichan: l’une des entrées n’est pas un dictionnaire.".into()))
         }}
-        )).await?.into_iter().filter_map(|x| x).collect())
+        ), self.api_concurrency).await?;
+        let mut messages = HashMap::new();
+        for (object_id, message) in chargement {
+            match message {
+                Some(message) => {
+                    messages.insert(object_id, message);
+                },
+                None => {
+                    self.to_recreate.insert(object_id);
+                }
+            }
+        }
+        Ok(messages)
     }
 
     /* Retrouve les objets de l’Affichan d’après les messages déjà présents dans le salon Discord. Fonction utilisée dans init. */
     async fn _load_from_messages(&self, database: &HashMap<u64, T>, self_id: &UserId, messages: Vec<Message>, ctx: &Context) -> Result<HashMap<u64, Message>, Error> {
-        println!("Chargement à partir des messages…");
+        info!("Chargement à partir des messages…");
         let self_messages = &self.messages;
 
-        Ok(try_join_all(messages.iter().filter(|message|
+        Ok(_try_join_limited(messages.iter().filter(|message|
             message.author.id.get() == self_id.get()
                 && !message.embeds.is_empty()
         )
             .filter_map(|message| message.embeds.get(0).unwrap().footer.as_ref().and_then(|footer| Some((message, footer))))
-            .filter_map(|(message, footer)| footer.text.parse().ok().and_then(|footer_text| Some((message, footer_text))))
+            .filter_map(|(message, footer)| T::parse_footer_id(&footer.text).and_then(|footer_text| Some((message, footer_text))))
             .map(|(message, footer_text)| async move {
                 if let Some(object) = database.get(&footer_text) {
                     if !self_messages.contains_key(&object.get_id()) {
                         Ok(Some((object.get_id(), message.clone())))
                     } else {
-                        eprintln!("Message {} en trop: suppression.", message.id);
+                        warn!("Message {} en trop: suppression.", message.id);
                         let res = message.delete(ctx).await;
                         res.and_then(|_| Ok(None))
                     }
                 } else {
-                    eprintln!("Message {} sans objet associé: message supprimé.", message.id);
+                    warn!("Message {} sans objet associé: message supprimé.", message.id);
                     let res = message.delete(ctx).await;
                     res.and_then(|_| Ok(None))
                 }
-            })).await?
+            }), self.api_concurrency).await?
             .into_iter().filter_map(|option| option).collect())
     }
 
@@ -129,10 +268,22 @@ impl<T: Object> Affichan<T> {
     /// qu’une seule fois et est fait automatiquement dans le setup (défini dans [`Bot::new`]) pour
     /// tous les Affichan déclarés.
     ///
-    /// Appelle [`Affichan::update`] après le chargement des messages.
+    /// Appelle [`Affichan::update`] après le chargement des messages. Les objets dont le message
+    /// sauvegardé n’a pas été retrouvé (message supprimé pendant que le bot était éteint) sont
+    /// republiés par cette mise à jour s’ils correspondent toujours aux critères de l’affichan,
+    /// avec les autres objets à ajouter et dans le même ordre (voir [`tools::sort_by_date`]).
     pub async fn init(&mut self, database: &HashMap<u64, T>, self_id: &UserId, saved_data: Option<&Yaml>, ctx: &SerenityContext) -> Result<(), ErrType> {
         self._load(ctx).await?;
 
+        /* Les anciennes sauvegardes ne contiennent que le tableau des messages */
+        let saved_data = saved_data.map(|saved_data| if saved_data.as_hash().is_some() {
+            self.forced_in = Self::_load_forced(&saved_data["forced_in"]);
+            self.forced_out = Self::_load_forced(&saved_data["forced_out"]);
+            &saved_data["messages"]
+        } else {
+            saved_data
+        });
+
         self.messages = match saved_data {
             Some(saved_data) => self._load_from_save(saved_data, ctx).await,
             None => self._load_from_messages(database, self_id, tools::get_channel_messages(self.chan.get()?, ctx, None).await?, ctx).await
@@ -147,75 +298,152 @@ impl<T: Object> Affichan<T> {
     /// y être.
     ///
     /// Utilisée par [`Bot::update_affichans`] qui appelle cette fonction pour tous les Affichans.
+    ///
+    /// Si aucun objet n’a été ajouté, supprimé ou modifié ([`Object::is_modified`]) dans la base
+    /// de données depuis la dernière mise à jour, et que l’affichan n’a pas été modifié entre-temps
+    /// (objets forcés, messages retirés), la mise à jour s’arrête immédiatement sans appliquer le
+    /// test aux objets. Si le test dépend d’autre chose que de l’objet (la date actuelle par
+    /// exemple), appeler [`Affichan::invalidate`] pour forcer une mise à jour complète.
+    ///
+    /// Le message d’un objet modifié n’est édité que si son contenu ([`Object::get_message_edit`])
+    /// diffère de celui envoyé lors de la dernière publication ou édition par l’affichan.
+    ///
+    /// Les nouveaux objets sont publiés un par un dans l’ordre de [`tools::sort_by_date`], du
+    /// dernier au premier, les objets épinglés en dernier.
     pub async fn update(&mut self, database: &HashMap<u64, T>, ctx: &SerenityContext) -> Result<(), ErrType> {
+        if self.to_recreate.is_empty() && self._unchanged(database) {
+            self._log_update(0, 0, 0);
+            return Ok(());
+        }
+        self.known_ids = None;
 
         /* Met à jour les objets déjà présents dans la base de données */
-        let edit_fails = self._edit_messages_if_modified(database, ctx).await;
+        let (edit_fails, edites) = self._edit_messages_if_modified(database, ctx).await;
 
         let mut deleted_elements = Vec::new();
 
         self.messages.retain(|object_id, message| { 
                 let keep = /* on garde si */
-                    database.contains_key(object_id) && /* dans la bdd */
-                    (self.test)(database.get(object_id)) && /* true au test */
+                    _accepte(&*self.test, &self.forced_in, &self.forced_out, database, object_id) && /* dans la bdd et true au test ou forcé */
                     !edit_fails.contains(object_id);
                 if !keep {
                     deleted_elements.push(take(message));
+                    self.embed_hashes.remove(object_id);
                 }
                 keep
             }
         );
 
-        join_all(
+        _join_limited(
             deleted_elements.iter().map(|message| async {
                 if let Err(e) = message.delete(ctx).await {
-                    eprint!("Impossible de supprimer l'un des messages : {e}");
+                    warn!("Impossible de supprimer l'un des messages : {e}");
                 }
-            })
+            }), self.api_concurrency
         ).await;
+        let mut supprimes = deleted_elements.len();
 
-        let self_chan = &self.chan;
-        let self_test = &self.test;
-
-        self.messages.extend(try_join_all(
-            tools::sort_by_date(self._get_new_valid_objects_from_db(database, self_test))
-                .into_iter().rev().map(|(&object_id, object)| async move {
-                        Ok::<_, ErrType>(
-                            (object_id, self_chan.get()?.send_message(ctx, object.get_message()).await?)
-                        )
-                })
-            ).await?
-        );
+        /* Les objets épinglés déjà publiés sont republiés après les nouveaux objets pour rester en
+         * bas du salon */
+        if !self._get_new_valid_objects_from_db(database).is_empty() {
+            let pinned: Vec<u64> = self.messages.keys()
+                .filter(|object_id| database.get(object_id).is_some_and(Object::is_pinned))
+                .copied().collect();
+            let pinned_messages: Vec<Message> = pinned.iter()
+                .filter_map(|object_id| {
+                    self.embed_hashes.remove(object_id);
+                    self.messages.remove(object_id)
+                }).collect();
+            supprimes += pinned_messages.len();
+            _join_limited(
+                pinned_messages.iter().map(|message| async {
+                    if let Err(e) = message.delete(ctx).await {
+                        warn!("Impossible de supprimer le message d’un objet épinglé : {e}");
+                    }
+                }), self.api_concurrency
+            ).await;
+        }
+
+        let mut crees = 0;
+
+        /* Publication un par un : les envois simultanés ne garantissent pas l’ordre des messages dans
+         * le salon, et les objets épinglés doivent arriver après tous les autres. */
+        for (&object_id, object) in tools::sort_by_date(self._get_new_valid_objects_from_db(database)).into_iter().rev() {
+            let message = _publish(self.chan.get()?, ctx, object).await?;
+            self.messages.insert(object_id, message);
+            self.embed_hashes.insert(object_id, _empreinte(object));
+            crees += 1;
+        }
+        self._log_update(edites, supprimes, crees);
+        for object_id in take(&mut self.to_recreate) {
+            if self.messages.contains_key(&object_id) {
+                info!("Message de l’objet {object_id} recréé.");
+            } else {
+                info!("Message de l’objet {object_id} non recréé : l’objet n’appartient plus à l’affichan.");
+            }
+        }
+        if edit_fails.is_empty() {
+            self.known_ids = Some(database.keys().copied().collect());
+        }
         Ok(())
     }
 
+    /* Renvoie true si la base de données n’a pas changé depuis la dernière mise à jour complète :
+     * mêmes identifiants et aucun objet modifié. */
+    fn _unchanged(&self, database: &HashMap<u64, T>) -> bool {
+        self.known_ids.as_ref().is_some_and(|known_ids| known_ids.len() == database.len()
+            && database.iter().all(|(id, object)| !object.is_modified() && known_ids.contains(id)))
+    }
+
+    /// Force une mise à jour complète de l’affichan au prochain appel à [`Affichan::update`], même
+    /// si la base de données n’a pas changé.
+    pub fn invalidate(&mut self) {
+        self.known_ids = None;
+    }
+
     /* Renvoie tous les objets de la bdd qui ne sont pas déjà présents dans l’Affichan et
-     * qui passent la fonction test. */
-    fn _get_new_valid_objects_from_db<'a>(&self, database: &'a HashMap<u64, T>, test: &Box<dyn Fn(Option<&T>) -> bool + Sync + Send + 'static>) -> Vec<(&'a u64, &'a T)> {
+     * qui passent la fonction test ou y sont forcés. */
+    fn _get_new_valid_objects_from_db<'a>(&self, database: &'a HashMap<u64, T>) -> Vec<(&'a u64, &'a T)> {
         database.iter()
-            .filter(|(id, obj)|
-                (*test)(Some(obj)) && !self.messages.contains_key(id)
+            .filter(|(id, _)|
+                _accepte(&*self.test, &self.forced_in, &self.forced_out, database, id) && !self.messages.contains_key(id)
             ).collect()
     }
 
     /* Modifie tous les écrits valides (présents dans la BDD & respectant le test) et renvoie ceux
-     * dont la modification a échoué (message inexistant le plus souvent).
+     * dont la modification a échoué (message inexistant le plus souvent), ainsi que le nombre de
+     * messages édités avec succès.
      * Fonction utilisée dans update.
      */
-    async fn _edit_messages_if_modified(&mut self, database: &HashMap<u64, T>, ctx: &Context) -> Vec<u64> {
-        join_all(self.messages.iter_mut().filter(|(object_id, _)|
-             (self.test)(database.get(object_id)) && database.get(object_id).is_some_and(|object| object.is_modified())
-        ).map(|(object_id, message)| async {
-            match message.edit(ctx, database.get(object_id).unwrap().get_message_edit()).await {
-                Err(_) => Some(*object_id),
-                Ok(_) => None
+    async fn _edit_messages_if_modified(&mut self, database: &HashMap<u64, T>, ctx: &Context) -> (Vec<u64>, usize) {
+        let embed_hashes = &self.embed_hashes;
+        let api_concurrency = self.api_concurrency;
+        let results = _join_limited(self.messages.iter_mut().filter_map(|(object_id, message)|
+             database.get(object_id).filter(|object| object.is_modified()
+                 && _accepte(&*self.test, &self.forced_in, &self.forced_out, database, object_id))
+                 .map(|object| (object_id, message, object, _empreinte(object)))
+                 /* Le drapeau modified peut être activé sans que le contenu n’ait changé : on évite
+                  * alors un appel à l’API inutile. */
+                 .filter(|(object_id, _, _, empreinte)| embed_hashes.get(*object_id) != Some(empreinte))
+        ).map(|(object_id, message, object, empreinte)| async move {
+            (*object_id, message.edit(ctx, object.get_message_edit()).await.map(|_| empreinte))
+        }), api_concurrency).await;
+
+        let mut edit_fails = Vec::new();
+        let tentatives = results.len();
+        for (object_id, result) in results {
+            match result {
+                Ok(empreinte) => {
+                    self.embed_hashes.insert(object_id, empreinte);
+                },
+                Err(_) => {
+                    self.embed_hashes.remove(&object_id);
+                    edit_fails.push(object_id);
+                }
             }
-        })).await
-            /* On doit exécuter d’abord les futures avant de savoir si c’est Err ou Ok, d’où cette
-             * suite de fonctions un peu étrange où on utilise map pour faire des options puis
-             * filter_map pour les enlever ensuite au lieu d’utiliser filter_map directement,
-             * puisque le map génère en fait des future */
-            .into_iter().filter_map(|x| x).collect()
+        }
+        let edites = tentatives - edit_fails.len();
+        (edit_fails, edites)
     }
 
     /// Appelle [`Affichan::refresh`] et supprime en plus tous les objets de l’affichan. Les objets valides
@@ -223,6 +451,8 @@ impl<T: Object> Affichan<T> {
     pub async fn purge(&mut self, ctx: &SerenityContext) -> Result<(), ErrType> {
         self.refresh(ctx).await?;
         self.messages.clear();
+        self.embed_hashes.clear();
+        self.known_ids = None;
         Ok(())
     }
 
@@ -231,29 +461,56 @@ impl<T: Object> Affichan<T> {
     /// [`Affichan::check_message_deletion`] pour tous les Affichan. Les messages seront donc republiés par
     /// la suite. N’a aucun impact sur la liste des objets de l’affichan, seulement sur les messages.
     pub async fn refresh(&mut self, ctx: &SerenityContext) -> Result<(), ErrType> {
-        try_join_all(self.messages.iter_mut().map(|(_, message)| message.delete(ctx))).await?;
+        let api_concurrency = self.api_concurrency;
+        _try_join_limited(self.messages.iter_mut().map(|(_, message)| message.delete(ctx)), api_concurrency).await?;
         Ok(())
     }
 
     /// Vérifie si un message supprimé correspond à un message de l’affichan. Si c’est le cas,
     /// republie le message en question.
     pub async fn check_message_deletion(&self, bot: &Bot<T>, ctx: &SerenityContext, message_id: &MessageId) -> Result<(), ErrType> {
-        try_join_all(
+        _try_join_limited(
             self.messages.iter().filter(|(_, message)| message.id.get() == message_id.get())
                 /* Ne peut trouver qu’un seul résultat maximum, mais on fait comme si quand-même */
                 .map(|(object_id, _)| async {
                     match self.chan.get() {
                         Ok(chan) => match bot.database.get(object_id) {
-                            Some(object) => chan.send_message(ctx, object.get_message()).await.or_else(|err| Err(err.into())),
+                            Some(object) => _publish(chan, ctx, object).await,
                             None => Err(Error::ObjectNotFound(format!("Objet {} référencé dans un message supprimé dans Affichan {} (id: {})", *object_id, chan.name, chan.id)))
                         }
                         Err(e) => Err(e)
                     }
-                })
+                }), self.api_concurrency
         ).await?;
         Ok(())
     }
 
+    /// Vérifie si un message modifié correspond à un message de l’affichan dont l’embed a été
+    /// masqué manuellement (drapeau `SUPPRESS_EMBEDS`). Si c’est le cas, réécrit le message à
+    /// partir de l’objet ([`Object::get_message_edit`]) en réaffichant l’embed.
+    ///
+    /// Les autres modifications sont ignorées, en particulier celles faites par le bot lui-même
+    /// (réécritures et mises à jour), qui ne masquent jamais l’embed : une réécriture ne peut donc
+    /// pas en déclencher une autre.
+    pub async fn check_message_edit(&mut self, database: &HashMap<u64, T>, ctx: &SerenityContext, event: &MessageUpdateEvent) -> Result<(), ErrType> {
+        let embed_masque = event.flags.flatten()
+            .is_some_and(|flags| flags.contains(MessageFlags::SUPPRESS_EMBEDS));
+        if !embed_masque {
+            return Ok(());
+        }
+        let Some((object_id, message)) = self.messages.iter_mut()
+            .find(|(_, message)| message.id == event.id) else {
+            return Ok(());
+        };
+        let chan = self.chan.get()?;
+        let object = database.get(object_id).ok_or(Error::ObjectNotFound(
+            format!("Objet {object_id} référencé dans un message modifié dans Affichan {} (id: {})", chan.name, chan.id)))?;
+        warn!("Embed du message {} de l’affichan {} masqué manuellement : réécriture.", message.id, chan.name);
+        message.edit(ctx, object.get_message_edit().suppress_embeds(false)).await?;
+        self.embed_hashes.insert(*object_id, _empreinte(object));
+        Ok(())
+    }
+
     /// Supprime un message particulier de l’affichan. Cette suppression sera détectée par
     /// `Bot::check_deletions`, qui appelle [`Affichan::check_message_deletion`] qui republiera le message.
     /// Le principal intérêt de cette méthode est de remettre un message en bas du salon.
@@ -276,6 +533,82 @@ impl<T: Object> Affichan<T> {
                     self.chan.get()?.name, self.chan.get()?.id)))?
             .delete(ctx).await?;
         self.messages.remove(object_id);
+        self.embed_hashes.remove(object_id);
+        self.known_ids = None;
+        Ok(())
+    }
+
+    /// Nettoie le salon d’affichage des messages du bot en trop, sans recréer de message : seul le
+    /// message connu de chaque objet est conservé. Les messages du bot dont l’embed ne référence
+    /// aucun objet ([`Object::parse_footer_id`]), ou un objet déjà affiché par un autre message,
+    /// sont supprimés. Un message affichant un objet qui devrait appartenir à l’affichan mais dont
+    /// le message n’était pas connu est conservé et devient le message de l’objet.
+    ///
+    /// Les messages sans embed et ceux des autres utilisateurs ne sont pas touchés. Renvoie le
+    /// nombre de messages supprimés.
+    pub async fn clean(&mut self, database: &HashMap<u64, T>, self_id: &UserId, ctx: &SerenityContext) -> Result<usize, ErrType> {
+        let messages = tools::get_channel_messages(self.chan.get()?, ctx, None).await?;
+        let mut en_trop = Vec::new();
+        for message in messages.into_iter().filter(|message| message.author.id == *self_id && !message.embeds.is_empty()) {
+            let object_id = message.embeds.first()
+                .and_then(|embed| embed.footer.as_ref())
+                .and_then(|footer| T::parse_footer_id(&footer.text));
+            match object_id {
+                Some(object_id) if self.messages.get(&object_id).is_some_and(|connu| connu.id == message.id) => {},
+                Some(object_id) if !self.messages.contains_key(&object_id) && self.accepts(database, &object_id) => {
+                    /* Contenu inconnu : pas d’empreinte, le message sera réécrit à la prochaine modification */
+                    self.messages.insert(object_id, message);
+                },
+                _ => en_trop.push(message)
+            }
+        }
+        let supprimes = _join_limited(en_trop.iter().map(|message| async move {
+            match message.delete(ctx).await {
+                Ok(()) => true,
+                Err(e) => {
+                    warn!("Impossible de supprimer le message en trop {} : {e}", message.id);
+                    false
+                }
+            }
+        }), self.api_concurrency).await.into_iter().filter(|supprime| *supprime).count();
+        info!("{supprimes} messages en trop supprimés du salon d’affichage {}.", self.get_chan_id());
+        self.known_ids = None;
+        Ok(supprimes)
+    }
+
+    /// Rend l’affichage d’un objet conforme à l’état attendu, indépendamment des drapeaux « modifié »
+    /// et de l’état mémorisé par l’affichan : le message est publié si l’objet doit appartenir à
+    /// l’affichan ([`Affichan::accepts`]) et n’y est pas, supprimé s’il n’en fait plus partie, et
+    /// réécrit sinon. Si la réécriture échoue (message supprimé entre-temps), le message est
+    /// republié.
+    pub async fn resync_object(&mut self, database: &HashMap<u64, T>, ctx: &SerenityContext, object_id: u64) -> Result<(), ErrType> {
+        let accepte = self.accepts(database, &object_id);
+        let object = database.get(&object_id).filter(|_| accepte);
+        match (self.messages.remove(&object_id), object) {
+            (Some(message), None) => {
+                self.embed_hashes.remove(&object_id);
+                if let Err(e) = message.delete(ctx).await {
+                    warn!("Impossible de supprimer le message de l’objet {object_id} : {e}");
+                }
+            },
+            (Some(mut message), Some(object)) => {
+                let message = match message.edit(ctx, object.get_message_edit()).await {
+                    Ok(()) => message,
+                    Err(e) => {
+                        warn!("Impossible de réécrire le message de l’objet {object_id}, republication : {e}");
+                        _publish(self.chan.get()?, ctx, object).await?
+                    }
+                };
+                self.messages.insert(object_id, message);
+                self.embed_hashes.insert(object_id, _empreinte(object));
+            },
+            (None, Some(object)) => {
+                self.messages.insert(object_id, _publish(self.chan.get()?, ctx, object).await?);
+                self.embed_hashes.insert(object_id, _empreinte(object));
+            },
+            (None, None) => {}
+        }
+        self.known_ids = None;
         Ok(())
     }
 
@@ -286,14 +619,87 @@ impl<T: Object> Affichan<T> {
     /// ne modifie que les objet ayant le drapeau `modified` activé, qui passe les erreurs et renvoie
     /// les identifiants des objets dont la modification a échoué.
     pub async fn edit_all_messages(&mut self, bot: &Bot<T>, ctx: &SerenityContext) -> Result<(), ErrType> {
-        try_join_all(
+        let api_concurrency = self.api_concurrency;
+        _try_join_limited(
             self.messages.iter_mut().filter_map(|(object_id, message)| bot.database.get(object_id)
                 .map_or_else(|| None, |object| Some((object, message))))
-            .map(|(object, message)| message.edit(ctx, object.get_message_edit()))
+            .map(|(object, message)| message.edit(ctx, object.get_message_edit())), api_concurrency
         ).await?;
+        self.embed_hashes = self.messages.keys()
+            .filter_map(|object_id| bot.database.get(object_id).map(|object| (*object_id, _empreinte(object))))
+            .collect();
         Ok(())
     }
 
+    /// Force l’objet donné à apparaître dans l’affichan, indépendamment du test. Annule un
+    /// éventuel [`Affichan::force_out`] sur cet objet. Prend effet au prochain appel
+    /// à [`Affichan::update`].
+    pub fn force_in(&mut self, object_id: u64) {
+        self.forced_out.remove(&object_id);
+        self.forced_in.insert(object_id);
+        self.known_ids = None;
+    }
+
+    /// Force l’objet donné à ne pas apparaître dans l’affichan, indépendamment du test. Annule
+    /// un éventuel [`Affichan::force_in`] sur cet objet. Prend effet au prochain appel
+    /// à [`Affichan::update`].
+    pub fn force_out(&mut self, object_id: u64) {
+        self.forced_in.remove(&object_id);
+        self.forced_out.insert(object_id);
+        self.known_ids = None;
+    }
+
+    /// Retire l’objet donné des objets forcés : sa présence dans l’affichan dépend à nouveau
+    /// uniquement du test. Prend effet au prochain appel à [`Affichan::update`].
+    pub fn clear_forced(&mut self, object_id: u64) {
+        self.forced_in.remove(&object_id);
+        self.forced_out.remove(&object_id);
+        self.known_ids = None;
+    }
+
+    /// Renvoie le nombre d’objets affichés dans l’affichan.
+    pub fn object_count(&self) -> usize {
+        self.messages.len()
+    }
+
+    /// Renvoie un itérateur sur les identifiants des objets affichés dans l’affichan, dans un
+    /// ordre quelconque.
+    pub fn iter_objects(&self) -> impl Iterator<Item = &u64> {
+        self.messages.keys()
+    }
+
+    /// Renvoie l’identifiant de l’objet affiché par le message donné, s’il appartient à l’affichan.
+    pub fn object_for_message(&self, message_id: &MessageId) -> Option<u64> {
+        self.messages.iter().find(|(_, message)| message.id == *message_id).map(|(&object_id, _)| object_id)
+    }
+
+    /// Vérifie si un objet devrait appartenir à l’affichan d’après son test et les objets forcés
+    /// (voir [`Affichan::force_in`] et [`Affichan::force_out`]), qu’il y soit déjà affiché ou non.
+    /// Renvoie `false` si l’objet n’existe pas dans la base de données.
+    pub fn accepts(&self, database: &HashMap<u64, T>, object_id: &u64) -> bool {
+        _accepte(&*self.test, &self.forced_in, &self.forced_out, database, object_id)
+    }
+
+    /* Définit le nombre maximal d’appels simultanés à l’API Discord. Voir Bot::api_concurrency. */
+    pub(crate) fn set_api_concurrency(&mut self, api_concurrency: usize) {
+        self.api_concurrency = api_concurrency;
+    }
+
+    /* Active la journalisation des mises à jour. Voir Bot::debug_affichans. */
+    pub(crate) fn set_debug(&mut self, debug: bool) {
+        self.debug = debug;
+    }
+
+    /* Journalise le bilan d’une mise à jour si le mode debug est activé */
+    fn _log_update(&self, edites: usize, supprimes: usize, crees: usize) {
+        if self.debug {
+            let chan = self.get_chan_id();
+            let intacts = self.messages.len().saturating_sub(edites + crees);
+            info!(chan, edites, supprimes, crees, intacts,
+                "Mise à jour de l’affichan {chan} : {edites} édités, {supprimes} supprimés, {crees} créés, {intacts} intacts.");
+        }
+    }
+
     /// Vérifie si un objet est contenu dans l’affichan.
     pub fn contains_object(&self, object_id: &u64) -> bool {
         self.messages.contains_key(object_id)
@@ -1,6 +1,8 @@
 use crate::{DataType, ErrType, Object};
 use poise::futures_util::FutureExt;
-use poise::{BoxFuture, Context};
+use poise::serenity_prelude::Permissions;
+use poise::{BoxFuture, Context, CreateReply};
+use std::sync::atomic::Ordering;
 
 ///! Ce module définit le type utilisé pour [`poise::structs::Command::custom_data`]. Actuellement,
 ///! il n'est utilisé que pour [`Permission`], mais il pourrait avoir d'autres usages à l'avenir.
@@ -14,6 +16,19 @@ use poise::{BoxFuture, Context};
 /// Note : il faut utiliser [`FutureExt::boxed`] après le bloc async.
 pub type CommandChecker<T> = dyn Fn(Context<'_, DataType<T>, ErrType>) -> BoxFuture<'_, Result<bool, ErrType>> + Send + Sync;
 
+/// Définit le type d'une fonction vérifiant si l'auteur d'une commande a un niveau de
+/// [`Permission`] donné, sans effet de bord (voir [`crate::Bot::permission_checker`]).
+///
+/// Elle prend deux paramètres : le [`Context`] de la commande en cours et la [`Permission`]
+/// à vérifier, et renvoie un bloc async boxé comme [`CommandChecker`].
+pub type PermissionChecker<T> = dyn Fn(Context<'_, DataType<T>, ErrType>, Permission) -> BoxFuture<'_, Result<bool, ErrType>> + Send + Sync;
+
+/// Définit le type d'une fonction associant à un niveau de [`Permission`] les permissions Discord
+/// requises pour voir les commandes de ce niveau (voir [`crate::Bot::member_permissions`]).
+///
+/// Des permissions vides laissent la commande visible par tous les membres.
+pub type MemberPermissions = dyn Fn(Permission) -> Permissions + Send + Sync;
+
 
 /// Ce type est la structure utilisée pour le [`poise::structs::Command::custom_data`].
 ///
@@ -38,10 +53,29 @@ impl CommandData {
 
     /// Commande de vérification appelant le champ `command_checker` de [`crate::Bot`], permettant
     /// ainsi à l'utilisateur de cette librairie de définir sa propre fonction de vérification.
+    ///
+    /// Si la vérification échoue (`false`) sans que la fonction de vérification n'ait répondu à
+    /// l'utilisateur, un message éphémère de refus lui est envoyé (voir
+    /// [`crate::Bot::permission_denied_message`]). Pour les commandes préfixées, dont on ne peut
+    /// pas savoir si une réponse a déjà été envoyée, le message est toujours envoyé.
     pub fn check<T: Object>(ctx: Context<'_, DataType<T>, ErrType>) -> BoxFuture<'_, Result<bool, ErrType>> {
         async move {
-            let bot = &mut ctx.data().lock().await;
-            (bot.command_checker)(ctx).await
+            /* Le verrou est relâché avant l'appel, la fonction pouvant avoir besoin du bot */
+            let command_checker = ctx.data().lock().await.command_checker.clone();
+            let autorise = command_checker(ctx).await?;
+            let deja_repondu = match ctx {
+                Context::Application(ctx) => ctx.has_sent_initial_response.load(Ordering::SeqCst),
+                Context::Prefix(_) => false
+            };
+            if !autorise && !deja_repondu {
+                let message = {
+                    let bot = ctx.data().lock().await;
+                    bot.permission_denied_message.clone()
+                        .unwrap_or_else(|| bot.tr("permission.refusee", &[]))
+                };
+                ctx.send(CreateReply::default().content(message).ephemeral(true)).await?;
+            }
+            Ok(autorise)
         }.boxed()
     }
 
@@ -53,7 +87,12 @@ impl CommandData {
 }
 
 /// Définit les différents niveaux de permission des commandes du bot.
-#[derive(Clone, Copy)]
+///
+/// Les niveaux standards ([`Permission::READ`], [`Permission::WRITE`], [`Permission::MANAGE`] et
+/// [`Permission::NONE`]) sont ceux des commandes fournies par la bibliothèque. Un bot peut définir
+/// ses propres niveaux avec [`Permission::Custom`], interprétés par ses fonctions de vérification
+/// (voir [`crate::Bot::command_checker`] et [`crate::Bot::permission_checker`]).
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
 pub enum Permission {
     /// La commande ne modifie pas la base de données.
     READ,
@@ -62,7 +101,19 @@ pub enum Permission {
     /// La commande modifie lourdement la base de données, dans le cadre de la gestion de cette dernière.
     MANAGE,
     /// La commande ne bénéficie d'aucun système de permissions (défaut).
-    NONE
+    NONE,
+    /// Niveau de permission propre au bot, identifié par son nom. La bibliothèque ne l'utilise
+    /// jamais pour ses propres commandes : son sens est entièrement défini par les fonctions de
+    /// vérification du bot, qui doivent donc prévoir un bras pour cette variante.
+    Custom(&'static str)
+}
+
+impl Permission {
+    /// Renvoie `true` s'il s'agit d'un niveau standard, c'est-à-dire de tout niveau autre que
+    /// [`Permission::Custom`].
+    pub fn is_standard(&self) -> bool {
+        !matches!(self, Permission::Custom(_))
+    }
 }
 
 impl Default for Permission {
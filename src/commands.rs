@@ -6,20 +6,25 @@ use super::ErrType;
 use super::Object;
 use crate::command_data::{CommandData, Permission};
 use crate::tools::get_object;
-use crate::tools::{alias, user_desc};
+use crate::tools::{aliases, user_desc};
+use crate::{generic_commands, Bot, ChangeEvent};
+use chrono::{DateTime, TimeDelta, Utc};
 use poise::Command;
 use poise::Context;
 use poise::{serenity_prelude as serenity, CreateReply};
 use serenity::all::CreateAttachment;
-use serenity::all::{CreateEmbed, CreateEmbedAuthor, Timestamp};
+use serenity::all::{CreateAllowedMentions, CreateEmbed, CreateEmbedAuthor, Timestamp};
 use serenity::futures::future::try_join_all;
+use std::collections::BTreeMap;
+use std::fs;
+use std::time::{Duration, Instant};
+use tracing::{info, info_span, Instrument};
 
 /// Renvoie l’embed « Aucun résultat » en indiquant la recherche de l’utilisateur.
-pub fn aucun_resultat(recherche: &str) -> CreateEmbed {
-    CreateEmbed::new()
-        .title("Aucun résultat.")
-        .color(16001600)
-        .author(CreateEmbedAuthor::new(format!("Recherche : {}", recherche)))
+pub fn aucun_resultat<T: Object>(bot: &Bot<T>, recherche: &str) -> CreateEmbed {
+    bot.get_theme().error_embed()
+        .title(bot.tr("recherche.aucun_resultat", &[]))
+        .author(CreateEmbedAuthor::new(bot.tr("recherche.auteur", &[("recherche", recherche)])))
         .timestamp(Timestamp::now())
 }
 
@@ -27,38 +32,199 @@ pub fn aucun_resultat(recherche: &str) -> CreateEmbed {
 ///
 /// Cette commande affiche tous les objets contenant le critère demandé.
 /// Un objet sera affiché dans les résultats s’il contient chaque mot du critère dans
-/// les mots de son nom.
+/// les mots de son nom. Les résultats les plus pertinents sont affichés en premier. Un mot
+/// précédé de - exclut les objets dont le nom le contient.
 #[poise::command(slash_command, category = "Recherche", custom_data = CommandData::perms(Permission::READ), check = CommandData::check)]
 pub async fn rechercher<T: Object>(
     ctx: Context<'_, DataType<T>, ErrType>,
     #[description = "Critère de recherche"] critere: String
 ) -> Result<(), ErrType> {
-    let bot = &mut ctx.data().lock().await;
-    let res = bot.search(critere.as_str());
-    if res.len() <= 3 && !res.is_empty() {
-        ctx.defer().await?;
-        try_join_all(
-            res.into_iter().map(|id| ctx.send(bot.database.get(&id).unwrap().get_reply()))
-        ).await?;
-    } else if res.is_empty() {
-        ctx.send(CreateReply::default().embed(aucun_resultat(critere.as_str()))).await?;
+    /* Le verrou est relâché avant l’envoi : soit quelques objets en réponses individuelles (voir
+     * Bot::inline_result_threshold), soit une liste paginée. */
+    let (reponses, embeds, ephemere) = {
+        let bot = ctx.data().lock().await;
+        let ephemere = bot.is_ephemeral_reply(&ctx);
+        let res: Vec<u64> = bot.search_scored(critere.as_str()).into_iter().map(|(id, _)| id).collect();
+        if res.len() <= bot.get_inline_result_threshold() && !res.is_empty() {
+            (res.into_iter().map(|id| bot.database.get(&id).unwrap().get_reply().ephemeral(ephemere)).collect(), Vec::new(), ephemere)
+        } else if res.is_empty() {
+            (Vec::new(), vec![aucun_resultat(&bot, critere.as_str())], ephemere)
+        } else {
+            let messages = tools::create_paged_list(res, |id|
+                tools::list_entry(bot.database.get(id).unwrap()),
+            1000);
+            let template = bot.get_theme().neutral_embed()
+                .title(bot.tr("recherche.titre", &[]))
+                .author(CreateEmbedAuthor::new(bot.tr("recherche.auteur", &[("recherche", &critere)])))
+                .timestamp(Timestamp::now());
+            (Vec::new(), bot.get_theme().multimessages(messages, template), ephemere)
+        }
+    };
+    if reponses.is_empty() {
+        Bot::send_embed_unlocked(&ctx, embeds).await
     } else {
-        let messages = tools::create_paged_list(res, |id|
-            bot.database.get(id).unwrap().get_list_entry(),
-        1000);
-        bot.send_embed(&ctx, tools::get_multimessages(messages, CreateEmbed::new()
-            .title("Résultatss de la recherche")
-            .author(CreateEmbedAuthor::new(format!("Recherche : {critere}")))
-            .timestamp(Timestamp::now())
-            .color(73887))).await?;
+        if ephemere {
+            ctx.defer_ephemeral().await?;
+        } else {
+            ctx.defer().await?;
+        }
+        try_join_all(reponses.into_iter().map(|reponse| ctx.send(reponse))).await?;
+        Ok(())
+    }
+}
+
+/// Recherche des objets en combinant plusieurs critères dans une seule requête.
+///
+/// Syntaxe : `nom:"la fondation" avant:01/01/2024 apres:01/01/2023`, ainsi que les filtres
+/// `cle:valeur` propres au bot (voir [`tools::parse_advanced_query`]).
+#[poise::command(slash_command, category = "Recherche", custom_data = CommandData::perms(Permission::READ), check = CommandData::check)]
+pub async fn recherche_avancee<T: Object>(ctx: Context<'_, DataType<T>, ErrType>,
+    #[description = "Requête, par exemple nom:\"fondation\" avant:01/01/2024"] requete: String) -> Result<(), ErrType> {
+    let resultats = {
+        let bot = ctx.data().lock().await;
+        match tools::parse_advanced_query_with(&requete, bot.get_timezone()) {
+            Ok(query) => bot.advanced_search(&query)
+                .map_err(|cle| bot.tr("recherche_avancee.filtre_inconnu", &[("cle", &cle)])),
+            Err(terme) => Err(bot.tr("recherche_avancee.terme_invalide", &[("terme", &terme)]))
+        }
+    };
+    match resultats {
+        Ok(objects) => generic_commands::_afficher_resultats(ctx, objects, requete).await,
+        Err(message) => Err(ErrType::CommandUseError(message))
     }
+}
+
+/// Affiche un objet d’après son identifiant.
+#[poise::command(slash_command, category = "Recherche", custom_data = CommandData::perms(Permission::READ), check = CommandData::check)]
+pub async fn objet<T: Object>(
+    ctx: Context<'_, DataType<T>, ErrType>,
+    #[description = "Identifiant de l’objet"] id: u64
+) -> Result<(), ErrType> {
+    let reply = {
+        let bot = ctx.data().lock().await;
+        match bot.database.get(&id) {
+            Some(object) => object.get_reply(),
+            None => CreateReply::default().content(bot.tr("objet.id_inconnu", &[]))
+        }.ephemeral(bot.is_ephemeral_reply(&ctx))
+    };
+    ctx.send(reply).await?;
     Ok(())
 }
 
+/// Affiche l’objet correspondant à un message d’un salon d’affichage.
+///
+/// Cette commande est accessible par clic droit sur un message (menu « Applications »). Le
+/// message doit avoir été publié par le bot dans l’un des salons d’affichage.
+#[poise::command(context_menu_command = "Objet du message", category = "Recherche", custom_data = CommandData::perms(Permission::READ), check = CommandData::check)]
+pub async fn chercher_id_par_message<T: Object>(
+    ctx: Context<'_, DataType<T>, ErrType>,
+    #[description = "Message d’un salon d’affichage"] message: serenity::Message
+) -> Result<(), ErrType> {
+    let reply = {
+        let bot = ctx.data().lock().await;
+        match bot.object_for_message(message.id).and_then(|id| bot.database.get(&id)) {
+            Some(object) => object.get_reply().ephemeral(bot.is_ephemeral_reply(&ctx)),
+            None => CreateReply::default().content(bot.tr("chercher_id_par_message.inconnu", &[])).ephemeral(true)
+        }
+    };
+    ctx.send(reply).await?;
+    Ok(())
+}
+
+/// Liste tous les objets de la base de données, triés par date.
+///
+/// Les objets sont affichés sous forme de liste à plusieurs pages, comme les résultats de la
+/// commande rechercher.
+#[poise::command(slash_command, category = "Recherche", custom_data = CommandData::perms(Permission::READ), check = CommandData::check)]
+pub async fn lister_tout<T: Object>(ctx: Context<'_, DataType<T>, ErrType>) -> Result<(), ErrType> {
+    /* Réponse à envoyer si la base de données est vide */
+    let (reponse_vide, titre) = {
+        let bot = ctx.data().lock().await;
+        (bot.database.is_empty().then(|| CreateReply::default()
+            .content(bot.tr("lister_tout.vide", &[]))
+            .ephemeral(bot.is_ephemeral_reply(&ctx))), bot.tr("recherche.tous", &[]))
+    };
+    if let Some(reply) = reponse_vide {
+        ctx.send(reply).await?;
+        return Ok(());
+    }
+    generic_commands::lister_filtered(ctx, Vec::new(), titre, false).await
+}
+
+/// Liste les commandes disponibles, par catégorie.
+///
+/// Seules les commandes auxquelles l’utilisateur a accès sont affichées (voir
+/// [`Bot::permission_checker`]). Les commandes accessibles uniquement par clic droit ne sont pas
+/// affichées.
+#[poise::command(slash_command, category = "Aide", custom_data = CommandData::perms(Permission::READ), check = CommandData::check)]
+pub async fn aide<T: Object>(ctx: Context<'_, DataType<T>, ErrType>) -> Result<(), ErrType> {
+    let (permission_checker, owner) = {
+        let bot = ctx.data().lock().await;
+        (bot.permission_checker.clone(), bot.owners.contains(&ctx.author().id))
+    };
+    let owner = owner || ctx.framework().options().owners.contains(&ctx.author().id);
+    let mut categories: BTreeMap<Option<&str>, Vec<&Command<DataType<T>, ErrType>>> = BTreeMap::new();
+    for command in &ctx.framework().options().commands {
+        let permission = command.custom_data.downcast_ref::<CommandData>()
+            .map(|data| data.permission).unwrap_or_default();
+        if command.slash_action.is_some() && (!command.owners_only || owner) && permission_checker(ctx, permission).await? {
+            categories.entry(command.category.as_deref()).or_default().push(command);
+        }
+    }
+    let bot = &mut ctx.data().lock().await;
+    let champs: Vec<(String, String)> = categories.into_iter().map(|(categorie, commandes)| (
+        categorie.map_or_else(|| bot.tr("aide.sans_categorie", &[]), str::to_string),
+        commandes.into_iter().map(|command| bot.tr("aide.commande", &[
+            ("nom", &command.name),
+            ("description", command.description.as_deref().unwrap_or_default())
+        ])).collect::<Vec<_>>().join("\n").chars().take(1024).collect()
+    )).collect();
+    let template = bot.get_theme().neutral_embed()
+        .title(bot.tr("aide.titre", &[]));
+    let pages = champs.chunks(4).map(|page| page.to_vec()).collect();
+    let embeds = bot.get_theme().multimessages_fields(pages, template);
+    bot.send_embed(&ctx, embeds).await
+}
+
 /// Commande de test pour vérifier que le bot fonctionne.
 #[poise::command(slash_command, category = "Salons d’affichage", custom_data = CommandData::perms(Permission::READ), check = CommandData::check)]
 pub async fn plop<T: Object>(ctx: Context<'_, DataType<T>, ErrType>) -> Result<(), ErrType> {
-    ctx.send(CreateReply::default().content("Plop !")).await?;
+    let reply = {
+        let bot = ctx.data().lock().await;
+        CreateReply::default().content(bot.tr("plop", &[])).ephemeral(bot.is_ephemeral_reply(&ctx))
+    };
+    ctx.send(reply).await?;
+    Ok(())
+}
+
+/// Mesure la latence du bot.
+///
+/// Affiche la latence du gateway Discord, mesurée par le heartbeat du shard, et le temps
+/// aller-retour de l’envoi d’un message.
+#[poise::command(slash_command, category = "Aide", custom_data = CommandData::perms(Permission::READ), check = CommandData::check)]
+pub async fn ping<T: Object>(ctx: Context<'_, DataType<T>, ErrType>) -> Result<(), ErrType> {
+    let mesure = {
+        let bot = ctx.data().lock().await;
+        CreateReply::default().content(bot.tr("ping.mesure", &[])).ephemeral(bot.is_ephemeral_reply(&ctx))
+    };
+    let debut = Instant::now();
+    let reponse = ctx.send(mesure).await?;
+    let aller_retour = debut.elapsed();
+    /* Le heartbeat n’a pas encore eu lieu si le shard vient de se connecter */
+    let latence_gateway = ctx.framework().shard_manager().runners.lock().await
+        .get(&ctx.serenity_context().shard_id)
+        .and_then(|runner| runner.latency);
+
+    let embed = {
+        let bot = ctx.data().lock().await;
+        let duree = |duree: Duration| bot.tr("ping.duree", &[("ms", &duree.as_millis().to_string())]);
+        bot.get_theme().success_embed()
+            .title(bot.tr("ping.titre", &[]))
+            .field(bot.tr("ping.gateway", &[]), latence_gateway.map_or_else(|| bot.tr("ping.inconnue", &[]), duree), true)
+            .field(bot.tr("ping.aller_retour", &[]), duree(aller_retour), true)
+            .timestamp(Timestamp::now())
+    };
+    reponse.edit(ctx, CreateReply::default().content("").embed(embed)).await?;
     Ok(())
 }
 
@@ -69,94 +235,349 @@ pub async fn plop<T: Object>(ctx: Context<'_, DataType<T>, ErrType>) -> Result<(
 /// soyez sûr d’entrer le bon nom.
 #[poise::command(slash_command, category = "Édition", custom_data = CommandData::perms(Permission::WRITE), check = CommandData::check)]
 pub async fn supprimer<T: Object>(ctx: Context<'_, DataType<T>, ErrType>,
-    #[description = "Critère d’identification de l’objet"] critere: String) -> Result<(), ErrType> {
+    #[autocomplete = "tools::autocomplete_object_name"] #[description = "Critère d’identification de l’objet"] critere: String) -> Result<(), ErrType> {
 
     let bot = &mut ctx.data().lock().await;
     if let Some(object_id) = get_object(&ctx, bot, &critere).await? {
-        bot.archive(vec![object_id]);
-        let ecrit_del = bot.database.remove(&object_id).unwrap();
+        let ecrit_del = bot.remove(object_id).unwrap();
         let ecrit_del = ecrit_del.get_name();
         ctx.send(CreateReply::default()
-            .content(format!("Objet « {ecrit_del} » supprimé."))).await?;
+            .content(bot.tr("supprimer.ok", &[("nom", ecrit_del)]))).await?;
         bot.log(&ctx, format!("{} a supprimé l'écrit {ecrit_del} (id: {object_id})", user_desc(ctx.author()))).await?;
-        bot.update_affichans(ctx.serenity_context()).await?;
+        bot._update_affichans_after_change(ctx.serenity_context(), Some(object_id)).await?;
     }
     Ok(())
 }
 
+/// Supprime tous les objets correspondant au critère, après confirmation s’il y en a plusieurs.
+///
+/// La suppression est annulable en une fois par la commande annuler.
+#[poise::command(slash_command, category = "Édition", custom_data = CommandData::perms(Permission::WRITE), check = CommandData::check)]
+pub async fn supprimer_lot<T: Object>(ctx: Context<'_, DataType<T>, ErrType>,
+    #[description = "Critère de recherche des objets"] critere: String) -> Result<(), ErrType> {
+    let objects = tools::get_objects(&ctx, &critere, 1, |bot, nombre| bot.tr("objet.lot.question", &[
+        ("nombre", &nombre.to_string()),
+        ("critere", &critere)
+    ])).await?;
+    if objects.is_empty() {
+        return Ok(());
+    }
+    let bot = &mut ctx.data().lock().await;
+    let supprimes = bot.purge_where(|object| objects.contains(&object.get_id()));
+    ctx.say(bot.tr("supprimer_lot.ok", &[("nombre", &supprimes.len().to_string())])).await?;
+    bot.log(&ctx, format!("{} a supprimé {} objets correspondant à la recherche « {critere} ».",
+        user_desc(ctx.author()), supprimes.len())).await?;
+    bot._update_affichans_after_change(ctx.serenity_context(), None).await?;
+    Ok(())
+}
+
 /// Annule la dernière action effectuée sur la base de données.
 #[poise::command(slash_command, category = "Édition", custom_data = CommandData::perms(Permission::WRITE), check = CommandData::check)]
 pub async fn annuler<T: Object>(ctx: Context<'_, DataType<T>, ErrType>) -> Result<(), ErrType> {
     let bot = &mut ctx.data().lock().await;
     if bot.annuler() {
-        ctx.send(CreateReply::default().content("Dernière modification annulée !")).await?;
+        ctx.send(CreateReply::default().content(bot.tr("annuler.ok", &[]))).await?;
         bot.log(&ctx, format!("{} a annulé une modification.", user_desc(ctx.author()))).await?;
     } else {
-        ctx.send(CreateReply::default().content("Aucune modification récente annulable.")).await?;
+        ctx.send(CreateReply::default().content(bot.tr("annuler.vide", &[]))).await?;
     }
     Ok(())
 }
 
+/// Vide l’historique des modifications, qui ne sont alors plus annulables.
+///
+/// Elle n'est accessible qu'aux utilisateurs qui gèrent le bot, définis par [`Bot::owners`].
+#[poise::command(slash_command, category = "Édition", owners_only)]
+pub async fn vider_historique<T: Object>(ctx: Context<'_, DataType<T>, ErrType>) -> Result<(), ErrType> {
+    let bot = &mut ctx.data().lock().await;
+    let nombre = bot.clear_history();
+    ctx.say(bot.tr("vider_historique.ok", &[("nombre", &nombre.to_string())])).await?;
+    bot.log(&ctx, format!("{} a vidé l'historique des modifications ({nombre} entrées).", user_desc(ctx.author()))).await?;
+    Ok(())
+}
+
+/// Affiche ce que la commande annuler va restaurer.
+///
+/// Pour chaque objet concerné par la dernière modification, affiche la différence entre son état
+/// actuel et l’état qui serait restauré.
+#[poise::command(slash_command, category = "Édition", custom_data = CommandData::perms(Permission::READ), check = CommandData::check)]
+pub async fn annuler_apercu<T: Object>(ctx: Context<'_, DataType<T>, ErrType>) -> Result<(), ErrType> {
+    let bot = &mut ctx.data().lock().await;
+    let Some(apercu) = bot.undo_preview() else {
+        ctx.send(CreateReply::default().content(bot.tr("annuler.vide", &[]))).await?;
+        return Ok(());
+    };
+    let champs: Vec<(String, String)> = apercu.into_iter().map(|(id, actuel, restaure)| {
+        let nom = restaure.or(actuel).map_or_else(String::new, |object| object.get_name().clone());
+        let valeur = match (actuel, restaure) {
+            (None, Some(restaure)) => format!("{}\n{}", bot.tr("annuler_apercu.restaure", &[]), restaure.summary()),
+            (Some(actuel), Some(restaure)) => tools::describe_diff(actuel, restaure)
+                .or_else(|| _diff(&actuel.summary(), &restaure.summary()))
+                .unwrap_or_else(|| bot.tr("annuler_apercu.identique", &[])),
+            _ => bot.tr("annuler_apercu.supprime", &[])
+        };
+        (format!("{nom} (id : {id})").chars().take(256).collect(), valeur.chars().take(1024).collect())
+    }).collect();
+    let template = bot.get_theme().neutral_embed()
+        .title(bot.tr("annuler_apercu.titre", &[]))
+        .timestamp(Timestamp::now());
+    let pages = champs.chunks(10).map(|page| page.to_vec()).collect();
+    let embeds = bot.get_theme().multimessages_fields(pages, template);
+    bot.send_embed(&ctx, embeds).await
+}
+
+/* Différence ligne à ligne entre deux résumés d’objet (voir Object::summary), dans un bloc de code
+   diff : lignes retirées précédées de -, lignes ajoutées de +. Renvoie None s’ils sont identiques. */
+fn _diff(actuel: &str, restaure: &str) -> Option<String> {
+    let retirees = actuel.lines().filter(|ligne| !restaure.lines().any(|l| l == *ligne)).map(|ligne| format!("- {ligne}"));
+    let ajoutees = restaure.lines().filter(|ligne| !actuel.lines().any(|l| l == *ligne)).map(|ligne| format!("+ {ligne}"));
+    let lignes: Vec<String> = retirees.chain(ajoutees).collect();
+    (!lignes.is_empty()).then(|| format!("```diff\n{}\n```", lignes.join("\n")))
+}
+
 /// Vérifie que les salons d’affichage sont bien à jour.
 #[poise::command(slash_command, category = "Salons d’affichage", custom_data = CommandData::perms(Permission::MANAGE), check = CommandData::check)]
 pub async fn update_affichans<T: Object>(ctx: Context<'_, DataType<T>, ErrType>) -> Result<(), ErrType> {
     ctx.defer().await?;
-    ctx.data().lock().await.update_affichans(ctx.serenity_context()).await?;
-    ctx.send(CreateReply::default().content("Affichans mis à jour.")).await?;
+    let bot = &mut ctx.data().lock().await;
+    bot.affichans.iter_mut().for_each(|affichan| affichan.invalidate());
+    bot.update_affichans(ctx.serenity_context()).await?;
+    ctx.send(CreateReply::default().content(bot.tr("update_affichans.ok", &[]))).await?;
+    Ok(())
+}
+
+/// Liste les objets affichés dans aucun salon d’affichage.
+///
+/// Les salons d’affichage sont mis à jour avant la recherche des objets orphelins.
+#[poise::command(slash_command, category = "Salons d’affichage", custom_data = CommandData::perms(Permission::READ), check = CommandData::check)]
+pub async fn orphelins<T: Object>(ctx: Context<'_, DataType<T>, ErrType>) -> Result<(), ErrType> {
+    let ephemere = ctx.data().lock().await.is_ephemeral_reply(&ctx);
+    if ephemere {
+        ctx.defer_ephemeral().await?;
+    } else {
+        ctx.defer().await?;
+    }
+    let bot = &mut ctx.data().lock().await;
+    bot.update_affichans(ctx.serenity_context()).await?;
+    let orphelins = tools::sort_by_date(bot.orphans().iter()
+        .filter_map(|id| bot.database.get_key_value(id)).collect())
+        .into_iter().map(|(&id, _)| id).collect::<Vec<_>>();
+    if orphelins.is_empty() {
+        ctx.send(CreateReply::default().content(bot.tr("orphelins.aucun", &[])).ephemeral(ephemere)).await?;
+    } else {
+        let messages = tools::create_paged_list(orphelins, |id|
+            tools::list_entry(bot.database.get(id).unwrap()),
+        1000);
+        let template = bot.get_theme().neutral_embed()
+            .title(bot.tr("orphelins.titre", &[]))
+            .timestamp(Timestamp::now());
+        let embeds = bot.get_theme().multimessages(messages, template);
+        bot.send_embed(&ctx, embeds).await?;
+    }
+    Ok(())
+}
+
+/// Liste les objets modifiés dont les salons d’affichage n’ont pas encore été mis à jour.
+///
+/// Les salons d’affichage ne sont pas mis à jour par cette commande (voir
+/// [`Bot::pending_modified`]).
+#[poise::command(slash_command, category = "Salons d’affichage", custom_data = CommandData::perms(Permission::READ), check = CommandData::check)]
+pub async fn modifies_en_attente<T: Object>(ctx: Context<'_, DataType<T>, ErrType>) -> Result<(), ErrType> {
+    let bot = &mut ctx.data().lock().await;
+    let modifies = bot.pending_modified();
+    if modifies.is_empty() {
+        ctx.send(CreateReply::default()
+            .content(bot.tr("modifies_en_attente.aucun", &[]))
+            .ephemeral(bot.is_ephemeral_reply(&ctx))).await?;
+    } else {
+        let messages = tools::create_paged_list(modifies, |id|
+            format!("{} (id : {id})", tools::list_entry(bot.database.get(id).unwrap())),
+        1000);
+        let template = bot.get_theme().neutral_embed()
+            .title(bot.tr("modifies_en_attente.titre", &[]))
+            .timestamp(Timestamp::now());
+        let embeds = bot.get_theme().multimessages(messages, template);
+        bot.send_embed(&ctx, embeds).await?;
+    }
     Ok(())
 }
 
 /// Renomme un objet.
+///
+/// L’ancien et le nouveau nom sont affichés dans la réponse et dans les logs. Le renommage est
+/// annulable par la commande annuler.
 #[poise::command(slash_command, category = "Édition", custom_data = CommandData::perms(Permission::WRITE), check = CommandData::check)]
 pub async fn renommer<T: Object>(ctx: Context<'_, DataType<T>, ErrType>,
-    #[description = "Critère d’identification de l’objet"] critere: String,
+    #[autocomplete = "tools::autocomplete_object_name"] #[description = "Critère d’identification de l’objet"] critere: String,
     #[description = "Nouveau nom de l’objet"] nouveau_nom: String) -> Result<(), ErrType> {
     let bot = &mut ctx.data().lock().await;
     if let Some(object_id) = get_object(&ctx, bot, &critere).await? {
+        let avant = bot.database.get(&object_id).unwrap().clone();
+        let nom = avant.get_name().to_string();
         bot.archive(vec![object_id]);
-        ctx.send(CreateReply::default().content(format!("Écrit {} renommé en {nouveau_nom} !",
-                                                        bot.database.get(&object_id).unwrap().get_name()))).await?;
-        bot.log(&ctx, format!("{} a renommé {} en {nouveau_nom} (id: {object_id})", user_desc(ctx.author()), bot.database.get(&object_id).unwrap().get_name())).await?;
-        bot.database.get_mut(&object_id).unwrap().set_name(nouveau_nom);
+        bot.database.get_mut(&object_id).unwrap().set_name(nouveau_nom.clone());
+        bot.mark_modified_by(object_id, ctx.author().id);
+        ctx.send(CreateReply::default().content(bot.tr("renommer.ok", &[
+            ("nom", &nom),
+            ("nouveau_nom", &nouveau_nom)
+        ]))).await?;
+        let details = tools::describe_diff(&avant, bot.database.get(&object_id).unwrap())
+            .map_or_else(String::new, |details| format!("\n{details}"));
+        bot.log(&ctx, format!("{} a renommé l'objet « {nom} » en « {nouveau_nom} » (id: {object_id}).{details}",
+            user_desc(ctx.author()))).await?;
     }
 
     Ok(())
 }
 
+/// Crée une copie d’un objet sous un nouveau nom.
+///
+/// La copie reçoit un nouvel identifiant (voir [`Bot::next_id`]) et conserve toutes les autres
+/// données de l’objet d’origine. La création est annulable par la commande annuler.
+#[poise::command(slash_command, category = "Édition", custom_data = CommandData::perms(Permission::WRITE), check = CommandData::check)]
+pub async fn cloner<T: Object>(ctx: Context<'_, DataType<T>, ErrType>,
+    #[autocomplete = "tools::autocomplete_object_name"] #[description = "Critère d’identification de l’objet à copier"] critere: String,
+    #[description = "Nom de la copie"] nouveau_nom: String) -> Result<(), ErrType> {
+    let bot = &mut ctx.data().lock().await;
+    if let Some(object_id) = get_object(&ctx, bot, &critere).await? {
+        let original = bot.database.get(&object_id).unwrap();
+        let nom = original.get_name().to_string();
+        let clone_id = bot.next_id();
+        let mut clone = original.clone();
+        clone.set_id(clone_id);
+        if clone.get_id() != clone_id {
+            ctx.say(bot.tr("cloner.non_supporte", &[])).await?;
+            return Ok(());
+        }
+        clone.set_name(nouveau_nom.clone());
+        if let Some(dates) = clone.as_timestamped_mut() {
+            dates.set_created_at(Utc::now());
+        }
+        bot.insert(clone);
+        ctx.send(CreateReply::default().content(bot.tr("cloner.ok", &[
+            ("nom", &nom),
+            ("nouveau_nom", &nouveau_nom),
+            ("id", &clone_id.to_string())
+        ]))).await?;
+        bot.log(&ctx, format!("{} a cloné l'objet « {nom} » (id: {object_id}) en « {nouveau_nom} » (id: {clone_id}).",
+            user_desc(ctx.author()))).await?;
+        bot._update_affichans_after_change(ctx.serenity_context(), Some(clone_id)).await?;
+    }
+
+    Ok(())
+}
+
+/// Indique le dernier utilisateur ayant modifié un objet.
+///
+/// Seuls les objets enregistrant cet utilisateur sont concernés (voir [`crate::object::Editable`]).
+/// Les modifications faites avant l’activation de cet enregistrement ne sont pas connues.
+#[poise::command(slash_command, category = "Recherche", custom_data = CommandData::perms(Permission::READ), check = CommandData::check)]
+pub async fn qui_a_modifie<T: Object>(ctx: Context<'_, DataType<T>, ErrType>,
+    #[autocomplete = "tools::autocomplete_object_name"] #[description = "Critère d’identification de l’objet"] critere: String) -> Result<(), ErrType> {
+    let bot = &mut ctx.data().lock().await;
+    if let Some(object_id) = get_object(&ctx, bot, &critere).await? {
+        let object = bot.database.get(&object_id).unwrap();
+        let nom = object.get_name();
+        let message = match object.as_editable().map(|edition| edition.last_editor()) {
+            None => bot.tr("qui_a_modifie.non_supporte", &[]),
+            Some(None) => bot.tr("qui_a_modifie.inconnu", &[("nom", nom)]),
+            Some(Some(user)) => bot.tr("qui_a_modifie.ok", &[("nom", nom), ("utilisateur", &format!("<@{user}>"))])
+        };
+        ctx.send(CreateReply::default().content(message)
+            .allowed_mentions(CreateAllowedMentions::new())
+            .ephemeral(bot.is_ephemeral_reply(&ctx))).await?;
+    }
+    Ok(())
+}
+
+/// Corrige la date d’un objet.
+///
+/// La date est à donner au format jj/mm/aaaa, et est interprétée à minuit dans le fuseau horaire
+/// du bot (voir [`Bot::timezone`]). Les salons d’affichage sont mis à jour en conséquence.
+///
+/// Cette commande ne fait pas partie de [`command_list`], [`Object::set_date`] n’ayant pas
+/// d’implémentation par défaut : les bots qui réimplémentent cette méthode doivent l’ajouter
+/// eux-mêmes à leur liste de commandes.
+#[poise::command(slash_command, category = "Édition", custom_data = CommandData::perms(Permission::WRITE), check = CommandData::check)]
+pub async fn set_date<T: Object>(ctx: Context<'_, DataType<T>, ErrType>,
+    #[autocomplete = "tools::autocomplete_object_name"] #[description = "Critère d’identification de l’objet"] critere: String,
+    #[description = "Nouvelle date de l’objet (jj/mm/aaaa)"] date: String) -> Result<(), ErrType> {
+    let bot = &mut ctx.data().lock().await;
+    let Some(timestamp) = bot.get_timezone().parse_date(&date) else {
+        return Err(ErrType::CommandUseError(bot.tr("set_date.format", &[("date", &date)])));
+    };
+    if let Some(object_id) = get_object(&ctx, bot, &critere).await? {
+        if !bot.database.get(&object_id).unwrap().has_date() {
+            return Err(ErrType::CommandUseError(bot.tr("set_date.sans_date", &[])));
+        }
+        bot.archive(vec![object_id]);
+        let avant = bot.database.get(&object_id).unwrap().clone();
+        bot.database.get_mut(&object_id).unwrap().set_date(timestamp);
+        bot.mark_modified_by(object_id, ctx.author().id);
+        let object = bot.database.get(&object_id).unwrap();
+        let nom = object.get_name().clone();
+        let details = tools::describe_diff(&avant, object)
+            .map_or_else(String::new, |details| format!("\n{details}"));
+        ctx.send(CreateReply::default().content(bot.tr("set_date.ok", &[("nom", &nom), ("date", &date)]))).await?;
+        bot.log(&ctx, format!("{} a changé la date de {nom} pour le {date} (id: {object_id}).{details}",
+            user_desc(ctx.author()))).await?;
+    }
+    Ok(())
+}
+
 /// Supprime les doublons de la base de données.
+///
+/// Les objets dont les noms sont identiques après normalisation sont des doublons, sauf si le bot
+/// compare les noms exactement (voir [`Bot::duplicate_matching`]).
 #[poise::command(slash_command, category = "Entretien de la base de données", custom_data = CommandData::perms(Permission::MANAGE), check = CommandData::check)]
 pub async fn doublons<T: Object>(ctx: Context<'_, DataType<T>, ErrType>) -> Result<(), ErrType> {
     ctx.defer().await?;
     let bot = &mut ctx.data().lock().await;
-    let database = &mut bot.database;
-    let (_, doublons) = database.iter().fold((Vec::new(), Vec::new()), |(names, to_del), (object_id, object)| {
-        if names.contains(&object.get_name()) {
-            (names, vec![to_del, vec![*object_id]].concat())
-        } else {
-            (vec![names, vec![object.get_name()]].concat(), to_del)
-        }
-    });
+    let doublons = bot.find_duplicates();
     let nb_deleted = doublons.len();
     bot.archive(doublons.clone());
-    /* Réemprunt après archive */
-    let database = &mut bot.database;
-    doublons.iter().for_each(|doublon| {database.remove(&doublon);});
+    doublons.iter().for_each(|doublon| {
+        bot.database.remove(doublon);
+        bot._update_search_index(*doublon);
+        bot.notify_change(ChangeEvent::Deleted(*doublon));
+    });
 
     ctx.send(CreateReply::default()
-        .content(if nb_deleted == 0 {
-            "Aucun doublon trouvé.".to_string()
-        } else {
-            let pluriel = if nb_deleted == 1 {"s"} else {""};
-            format!("{} doublon{pluriel} supprimé{pluriel}.", nb_deleted)
+        .content(match nb_deleted {
+            0 => bot.tr("doublons.aucun", &[]),
+            1 => bot.tr("doublons.supprime", &[("nombre", "1")]),
+            _ => bot.tr("doublons.supprimes", &[("nombre", &nb_deleted.to_string())])
         })).await?;
     bot.log(&ctx, format!("{} a nettoyé les doublons.", user_desc(ctx.author()))).await?;
     Ok(())
 }
 
+/// Supprime les objets plus anciens que le nombre de jours donné.
+///
+/// L’ancienneté est calculée d’après la date des objets ; les objets sans date ne sont jamais
+/// supprimés. La suppression est annulable en une fois par la commande annuler.
+#[poise::command(slash_command, category = "Entretien de la base de données", custom_data = CommandData::perms(Permission::MANAGE), check = CommandData::check)]
+pub async fn purger_anciens<T: Object>(ctx: Context<'_, DataType<T>, ErrType>,
+    #[description = "Ancienneté minimale en jours des objets à supprimer"] jours: u32) -> Result<(), ErrType> {
+    let bot = &mut ctx.data().lock().await;
+    let limite = (Utc::now() - TimeDelta::days(jours as i64)).timestamp();
+    let supprimes = bot.purge_where(|object| object.has_date() && object.get_date().unix_timestamp() < limite);
+    let jours = jours.to_string();
+    if supprimes.is_empty() {
+        ctx.say(bot.tr("purger_anciens.aucun", &[("jours", &jours)])).await?;
+    } else {
+        ctx.say(bot.tr("purger_anciens.ok", &[("nombre", &supprimes.len().to_string()), ("jours", &jours)])).await?;
+        bot.log(&ctx, format!("{} a supprimé {} objets de plus de {jours} jours.",
+            user_desc(ctx.author()), supprimes.len())).await?;
+        bot._update_affichans_after_change(ctx.serenity_context(), None).await?;
+    }
+    Ok(())
+}
+
 /// Remet un objet à l’avant des salons d’affichage
 #[poise::command(slash_command, category = "Salons d’affichage", custom_data = CommandData::perms(Permission::WRITE), check = CommandData::check)]
 pub async fn up<T: Object>(ctx: Context<'_, DataType<T>, ErrType>,
-    #[description = "Critère d’identification de l’objet."] critere: String) -> Result<(), ErrType> {
+    #[autocomplete = "tools::autocomplete_object_name"] #[description = "Critère d’identification de l’objet."] critere: String) -> Result<(), ErrType> {
     let bot = &mut ctx.data().lock().await;
     if let Some(object_id) = get_object(&ctx, bot, &critere).await? {
         try_join_all(bot.affichans.iter()
@@ -165,20 +586,217 @@ pub async fn up<T: Object>(ctx: Context<'_, DataType<T>, ErrType>,
         ).await?;
         bot.archive(vec![object_id]);
         bot.database.get_mut(&object_id).unwrap().up();
-        ctx.say(format!("Objet {} up !", bot.database.get(&object_id).unwrap().get_name())).await?;
+        ctx.say(bot.tr("up.ok", &[("nom", bot.database.get(&object_id).unwrap().get_name())])).await?;
         bot.log(&ctx, format!("{} a up {} (id: {object_id})", user_desc(ctx.author()), bot.database.get(&object_id).unwrap().get_name())).await?;
-        bot.update_affichans(ctx.serenity_context()).await?;
+        bot.update_affichan_for(ctx.serenity_context(), object_id).await?;
     }
     Ok(())
 }
 
+/// Supprime les messages en trop d’un salon d’affichage, sans recréer les messages des objets.
+///
+/// Seul un message par objet est conservé (voir [`Bot::clean_affichan`]). Contrairement à
+/// refresh_affichans, les messages valides ne sont pas supprimés.
+#[poise::command(slash_command, category = "Salons d’affichage", custom_data = CommandData::perms(Permission::MANAGE), check = CommandData::check)]
+pub async fn nettoyer_salon<T: Object>(ctx: Context<'_, DataType<T>, ErrType>,
+    #[description = "Salon d’affichage"] salon: serenity::Channel) -> Result<(), ErrType> {
+    ctx.defer().await?;
+    let bot = &mut ctx.data().lock().await;
+    match bot.clean_affichan(salon.id().get(), ctx.serenity_context()).await? {
+        Some(nombre) => {
+            ctx.say(bot.tr("nettoyer_salon.ok", &[("nombre", &nombre.to_string()), ("salon", &salon.to_string())])).await?;
+            bot.log(&ctx, format!("{} a nettoyé le salon {salon} ({nombre} messages supprimés).", user_desc(ctx.author()))).await?;
+        },
+        None => {
+            ctx.say(bot.tr("nettoyer_salon.salon_inconnu", &[])).await?;
+        }
+    }
+    Ok(())
+}
+
+/// Resynchronise l’affichage d’un objet dans tous les salons d’affichage.
+///
+/// Le message de l’objet est publié, supprimé ou réécrit dans chaque salon selon ses critères
+/// (voir [`Bot::resync_object`]).
+#[poise::command(slash_command, category = "Salons d’affichage", custom_data = CommandData::perms(Permission::MANAGE), check = CommandData::check)]
+pub async fn resync<T: Object>(ctx: Context<'_, DataType<T>, ErrType>,
+    #[autocomplete = "tools::autocomplete_object_name"] #[description = "Critère d’identification de l’objet."] critere: String) -> Result<(), ErrType> {
+    ctx.defer().await?;
+    let bot = &mut ctx.data().lock().await;
+    if let Some(object_id) = get_object(&ctx, bot, &critere).await? {
+        bot.resync_object(object_id, ctx.serenity_context()).await?;
+        let nom = bot.database.get(&object_id).unwrap().get_name().to_string();
+        ctx.say(bot.tr("resync.ok", &[("nom", &nom)])).await?;
+        bot.log(&ctx, format!("{} a resynchronisé l'affichage de {nom} (id: {object_id})", user_desc(ctx.author()))).await?;
+    }
+    Ok(())
+}
+
+/// Épingle un objet, ou le désépingle s’il l’est déjà.
+///
+/// Les objets épinglés restent en bas des salons d’affichage, quelle que soit leur date (voir
+/// [`Object::is_pinned`]). L’épinglage n’est disponible que si le bot implémente
+/// [`Object::set_pinned`].
+#[poise::command(slash_command, category = "Salons d’affichage", custom_data = CommandData::perms(Permission::WRITE), check = CommandData::check)]
+pub async fn epingler<T: Object>(ctx: Context<'_, DataType<T>, ErrType>,
+    #[autocomplete = "tools::autocomplete_object_name"] #[description = "Critère d’identification de l’objet."] critere: String) -> Result<(), ErrType> {
+    let bot = &mut ctx.data().lock().await;
+    if let Some(object_id) = get_object(&ctx, bot, &critere).await? {
+        let object = bot.database.get(&object_id).unwrap();
+        let epingle = !object.is_pinned();
+        let mut essai = object.clone();
+        essai.set_pinned(epingle);
+        if essai.is_pinned() != epingle {
+            ctx.say(bot.tr("epingler.non_supporte", &[])).await?;
+            return Ok(());
+        }
+        if epingle {
+            /* Le message est republié en bas du salon, comme pour up */
+            try_join_all(bot.affichans.iter()
+                .filter(|affichan| affichan.contains_object(&object_id))
+                .map(|affichan| affichan.up(ctx.serenity_context(), &object_id))
+            ).await?;
+        }
+        bot.archive(vec![object_id]);
+        bot.database.get_mut(&object_id).unwrap().set_pinned(epingle);
+        bot.mark_modified_by(object_id, ctx.author().id);
+        let nom = bot.database.get(&object_id).unwrap().get_name().to_string();
+        ctx.say(bot.tr(if epingle {"epingler.epingle"} else {"epingler.desepingle"}, &[("nom", &nom)])).await?;
+        bot.log(&ctx, format!("{} a {} {nom} (id: {object_id})", user_desc(ctx.author()),
+            if epingle {"épinglé"} else {"désépinglé"})).await?;
+        bot.update_affichan_for(ctx.serenity_context(), object_id).await?;
+    }
+    Ok(())
+}
+
+/// Mode d’affichage d’un objet dans un salon d’affichage, utilisé par la commande deplacer.
+#[derive(poise::ChoiceParameter)]
+pub enum ModeAffichage {
+    /// L’objet est affiché dans le salon, même s’il ne correspond pas à ses critères.
+    #[name = "Forcer dans le salon"]
+    Inclure,
+    /// L’objet n’est pas affiché dans le salon, même s’il correspond à ses critères.
+    #[name = "Forcer hors du salon"]
+    Exclure,
+    /// L’objet est affiché dans le salon selon ses critères habituels.
+    #[name = "Automatique"]
+    Automatique
+}
+
+/// Force l’affichage d’un objet dans un salon d’affichage, ou son retrait.
+///
+/// Permet de gérer les exceptions aux critères des salons d’affichage. Le mode automatique
+/// annule le forçage.
+#[poise::command(slash_command, category = "Salons d’affichage", custom_data = CommandData::perms(Permission::MANAGE), check = CommandData::check)]
+pub async fn deplacer<T: Object>(ctx: Context<'_, DataType<T>, ErrType>,
+    #[autocomplete = "tools::autocomplete_object_name"] #[description = "Critère d’identification de l’objet"] critere: String,
+    #[description = "Salon d’affichage"] salon: serenity::Channel,
+    #[description = "Mode d’affichage de l’objet dans le salon"] mode: ModeAffichage) -> Result<(), ErrType> {
+    let bot = &mut ctx.data().lock().await;
+    if let Some(object_id) = get_object(&ctx, bot, &critere).await? {
+        let Some(affichan) = bot.affichans.iter_mut().find(|affichan| affichan.get_chan_id() == salon.id().get()) else {
+            ctx.say(bot.tr("deplacer.salon_inconnu", &[])).await?;
+            return Ok(());
+        };
+        let (mode_desc, key) = match mode {
+            ModeAffichage::Inclure => {affichan.force_in(object_id); ("forcé dans", "deplacer.inclus")},
+            ModeAffichage::Exclure => {affichan.force_out(object_id); ("forcé hors de", "deplacer.exclu")},
+            ModeAffichage::Automatique => {affichan.clear_forced(object_id); ("remis en automatique dans", "deplacer.automatique")}
+        };
+        let nom = bot.database.get(&object_id).unwrap().get_name().to_string();
+        ctx.say(bot.tr(key, &[("nom", &nom), ("salon", &salon.to_string())])).await?;
+        bot.log(&ctx, format!("{} a {mode_desc} le salon {salon} l'objet {nom} (id: {object_id}).",
+            user_desc(ctx.author()))).await?;
+        bot.update_affichan_for(ctx.serenity_context(), object_id).await?;
+    }
+    Ok(())
+}
+
+/// Exporte la liste des objets d’un salon d’affichage dans un fichier texte.
+///
+/// Chaque ligne du fichier contient l’entrée de liste d’un objet, les objets étant triés par date
+/// (voir [`tools::sort_by_date`]).
+#[poise::command(slash_command, category = "Salons d’affichage", custom_data = CommandData::perms(Permission::READ), check = CommandData::check)]
+pub async fn export_affichan<T: Object>(ctx: Context<'_, DataType<T>, ErrType>,
+    #[description = "Salon d’affichage"] salon: serenity::Channel) -> Result<(), ErrType> {
+    let reply = {
+        let bot = ctx.data().lock().await;
+        let reply = match bot.affichan_by_chan(salon.id().get()) {
+            Some(affichan) => {
+                let objects = tools::sort_by_date(affichan.iter_objects()
+                    .filter_map(|id| bot.database.get_key_value(id))
+                    .collect());
+                let contenu = objects.iter()
+                    .map(|(_, object)| object.get_list_entry())
+                    .collect::<Vec<_>>().join("\n");
+                CreateReply::default()
+                    .content(bot.tr("export_affichan.ok", &[("nombre", &objects.len().to_string()), ("salon", &salon.to_string())]))
+                    .attachment(CreateAttachment::bytes(contenu, format!("affichan-{}.txt", salon.id())))
+            },
+            None => CreateReply::default().content(bot.tr("export_affichan.salon_inconnu", &[]))
+        };
+        reply.ephemeral(bot.is_ephemeral_reply(&ctx))
+    };
+    ctx.send(reply).await?;
+    Ok(())
+}
+
+/// Liste les objets effectivement affichés dans un salon d’affichage.
+///
+/// Contrairement à la liste des objets qui devraient y figurer, cette commande se fonde sur les
+/// messages que l’affichan garde en mémoire (publiés par le bot ou retrouvés à son
+/// initialisation), sans relire le salon Discord ni le mettre à jour : les objets affichés à
+/// tort (ne passant plus le test du salon ou absents de la base de données) et les objets qui
+/// devraient être affichés mais ne le sont pas sont signalés, ce qui permet de diagnostiquer un
+/// salon avant une mise à jour.
+#[poise::command(slash_command, category = "Salons d’affichage", custom_data = CommandData::perms(Permission::READ), check = CommandData::check)]
+pub async fn contenu_salon<T: Object>(ctx: Context<'_, DataType<T>, ErrType>,
+    #[description = "Salon d’affichage"] salon: serenity::Channel) -> Result<(), ErrType> {
+    let bot = &mut ctx.data().lock().await;
+    let Some(affichan) = bot.affichan_by_chan(salon.id().get()) else {
+        ctx.send(CreateReply::default()
+            .content(bot.tr("contenu_salon.salon_inconnu", &[]))
+            .ephemeral(bot.is_ephemeral_reply(&ctx))).await?;
+        return Ok(());
+    };
+    let affiches = tools::sort_by_date(affichan.iter_objects()
+        .filter_map(|id| bot.database.get_key_value(id))
+        .collect());
+    let mut lignes: Vec<String> = affiches.into_iter().map(|(id, object)| if affichan.accepts(&bot.database, id) {
+        tools::list_entry(object)
+    } else {
+        bot.tr("contenu_salon.en_trop", &[("entree", &tools::list_entry(object))])
+    }).collect();
+    let mut inconnus: Vec<&u64> = affichan.iter_objects().filter(|id| !bot.database.contains_key(id)).collect();
+    inconnus.sort();
+    lignes.extend(inconnus.into_iter().map(|id| bot.tr("contenu_salon.inconnu", &[("id", &id.to_string())])));
+    let manquants = tools::sort_by_date(bot.database.iter()
+        .filter(|(id, _)| !affichan.contains_object(id) && affichan.accepts(&bot.database, id))
+        .collect());
+    lignes.extend(manquants.into_iter().map(|(_, object)|
+        bot.tr("contenu_salon.manquant", &[("entree", &tools::list_entry(object))])));
+    if lignes.is_empty() {
+        ctx.send(CreateReply::default()
+            .content(bot.tr("contenu_salon.vide", &[("salon", &salon.to_string())]))
+            .ephemeral(bot.is_ephemeral_reply(&ctx))).await?;
+        return Ok(());
+    }
+    let messages = tools::create_paged_list(lignes, String::clone, 1000);
+    let nom = salon.clone().guild().map_or_else(|| salon.id().to_string(), |chan| chan.name);
+    let template = bot.get_theme().neutral_embed()
+        .title(bot.tr("contenu_salon.titre", &[("nombre", &affichan.object_count().to_string()), ("salon", &nom)]))
+        .timestamp(Timestamp::now());
+    let embeds = bot.get_theme().multimessages(messages, template);
+    bot.send_embed(&ctx, embeds).await
+}
+
 /// Réinitialise les messages des salons d’affichage.
 #[poise::command(slash_command, category = "Salons d’affichage", custom_data = CommandData::perms(Permission::MANAGE), check = CommandData::check)]
 pub async fn refresh_affichans<T: Object>(ctx: Context<'_, DataType<T>, ErrType>) -> Result<(), ErrType> {
     let bot = &mut ctx.data().lock().await;
     ctx.defer().await?;
     try_join_all(bot.affichans.iter_mut().map(|affichan| affichan.refresh(ctx.serenity_context()))).await?;
-    ctx.say("Messages des salons d’affichage réinitialisés.").await?;
+    ctx.say(bot.tr("refresh_affichans.ok", &[])).await?;
     bot.log(&ctx, format!("{} a nettoyé les salons d'affichage.", user_desc(ctx.author()))).await?;
     Ok(())
 }
@@ -190,26 +808,94 @@ pub async fn reset_affichans<T: Object>(ctx: Context<'_, DataType<T>, ErrType>)
     ctx.defer().await?;
     try_join_all(bot.affichans.iter_mut().map(|affichan| affichan.purge(ctx.serenity_context()))).await?;
     bot.update_affichans(ctx.serenity_context()).await?;
-    ctx.say("Salons d’affichage réinitialisés.").await?;
+    ctx.say(bot.tr("reset_affichans.ok", &[])).await?;
     bot.log(&ctx, format!("{} a réinitialisé les affichans.", user_desc(ctx.author()))).await?;
     Ok(())
 }
 
 /// Renvoie la base de données.
+///
+/// Le fichier envoyé est celui du stockage de la base de données (voir
+/// [`crate::storage::Storage::file_path`]) : la commande n’est pas disponible pour un stockage
+/// qui n’est pas contenu dans un fichier, comme `SqliteStorage`.
 #[poise::command(slash_command, category = "Base de données", custom_data = CommandData::perms(Permission::MANAGE), check = CommandData::check)]
 pub async fn bdd<T: Object>(ctx: Context<'_, DataType<T>, ErrType>) -> Result<(), ErrType> {
+    let fichier = {
+        let bot = ctx.data().lock().await;
+        bot._storage_file().ok_or_else(|| ErrType::CommandUseError(bot.tr("bdd.non_supporte", &[])))?
+    };
     ctx.defer().await?;
-    ctx.send(CreateReply::default().attachment(CreateAttachment::path(&ctx.data().lock().await.data_file).await?)).await?;
+    ctx.send(CreateReply::default().attachment(CreateAttachment::path(&fichier).await?)).await?;
     Ok(())
 }
 
 /// Renvoie le nombre d’objets dans la base de données.
 #[poise::command(slash_command, category = "Base de données", custom_data = CommandData::perms(Permission::READ), check = CommandData::check)]
 pub async fn taille_bdd<T: Object>(ctx: Context<'_, DataType<T>, ErrType>) -> Result<(), ErrType> {
-    ctx.send(CreateReply::default().content(
-        format!("Il y a actuellement {} écrits dans la base de données.",
-            ctx.data().lock().await.database.len())
-    )).await?;
+    let reply = {
+        let bot = ctx.data().lock().await;
+        CreateReply::default()
+            .content(bot.tr("taille_bdd", &[("nombre", &bot.database.len().to_string())]))
+            .ephemeral(bot.is_ephemeral_reply(&ctx))
+    };
+    ctx.send(reply).await?;
+    Ok(())
+}
+
+/// Affiche des statistiques générales sur le bot.
+///
+/// Sont affichés le nombre d’objets de la base de données, le nombre d’objets de chaque salon
+/// d’affichage, la date de la dernière mise à jour RSS, la taille du fichier de sauvegarde (si
+/// le stockage en a un, voir [`crate::storage::Storage::file_path`]) et le nombre de
+/// modifications annulables.
+#[poise::command(slash_command, category = "Base de données", custom_data = CommandData::perms(Permission::READ), check = CommandData::check)]
+pub async fn stats<T: Object>(ctx: Context<'_, DataType<T>, ErrType>) -> Result<(), ErrType> {
+    let bot = &mut ctx.data().lock().await;
+    let affichans = bot.affichan_counts().into_iter()
+        .map(|(salon, nombre)| bot.tr("stats.affichan", &[("salon", &salon.to_string()), ("nombre", &nombre.to_string())]))
+        .collect::<Vec<_>>().join("\n");
+    let taille = match bot._storage_file().map(fs::metadata) {
+        Some(Ok(metadata)) => bot.tr("stats.taille", &[("taille", &format!("{:.1}", metadata.len() as f64 / 1024.0))]),
+        Some(Err(_)) => bot.tr("stats.sauvegarde_absente", &[]),
+        None => bot.tr("stats.taille_indisponible", &[])
+    };
+    let embed = bot.get_theme().neutral_embed()
+        .title(bot.tr("stats.titre", &[]))
+        .field(bot.tr("stats.objets", &[]), bot.database.len().to_string(), true)
+        .field(bot.tr("stats.historique", &[]), bot.history_len().to_string(), true)
+        .field(bot.tr("stats.affichans", &[]), if affichans.is_empty() { bot.tr("stats.aucun_affichan", &[]) } else { affichans }, false)
+        .field(bot.tr("stats.dernier_rss", &[]), format!("<t:{}:f>", bot.last_rss_update.timestamp()), true)
+        .field(bot.tr("stats.taille_sauvegarde", &[]), taille, true)
+        .timestamp(Timestamp::now());
+    bot.send_embed(&ctx, vec![embed]).await
+}
+
+/// Affiche l’état du thread de mise à jour RSS.
+///
+/// Sont affichés l’exécution ou non du thread, la date de la dernière mise à jour réussie, la
+/// dernière erreur éventuelle et la date du dernier écrit récupéré.
+#[poise::command(slash_command, category = "Base de données", custom_data = CommandData::perms(Permission::READ), check = CommandData::check)]
+pub async fn rss_status<T: Object>(ctx: Context<'_, DataType<T>, ErrType>) -> Result<(), ErrType> {
+    let reply = {
+        let bot = ctx.data().lock().await;
+        let date = |date: DateTime<Utc>| format!("<t:{}:f>", date.timestamp());
+        let embed = bot.get_theme().neutral_embed()
+            .title(bot.tr("rss_status.titre", &[]))
+            .field(bot.tr("rss_status.thread", &[]),
+                   bot.tr(if bot.rss_running() {"rss_status.en_cours"} else {"rss_status.arrete"}, &[]), true)
+            .field(bot.tr("rss_status.dernier_succes", &[]),
+                   bot.last_rss_success().map_or_else(|| bot.tr("rss_status.jamais", &[]), date), true)
+            .field(bot.tr("stats.dernier_rss", &[]), date(bot.last_rss_update), true)
+            .field(bot.tr("rss_status.derniere_erreur", &[]), match bot.last_rss_error() {
+                /* Un champ d’embed est limité à 1024 caractères */
+                Some((date_erreur, erreur)) => bot.tr("rss_status.erreur", &[("date", &date(*date_erreur)),
+                    ("erreur", &erreur.chars().take(900).collect::<String>())]),
+                None => bot.tr("rss_status.aucune_erreur", &[])
+            }, false)
+            .timestamp(Timestamp::now());
+        CreateReply::default().embed(embed).ephemeral(bot.is_ephemeral_reply(&ctx))
+    };
+    ctx.send(reply).await?;
     Ok(())
 }
 
@@ -217,8 +903,9 @@ pub async fn taille_bdd<T: Object>(ctx: Context<'_, DataType<T>, ErrType>) -> Re
 #[poise::command(slash_command, category = "Base de données", custom_data = CommandData::perms(Permission::READ), check = CommandData::check)]
 pub async fn save<T: Object>(ctx: Context<'_, DataType<T>, ErrType>) -> Result<(), ErrType> {
     ctx.defer().await?;
-    ctx.data().lock().await.save()?;
-    ctx.say("Base de données sauvegardée !").await?;
+    let bot = ctx.data().lock().await;
+    bot.save()?;
+    ctx.say(bot.tr("save.ok", &[])).await?;
     Ok(())
 }
 
@@ -227,19 +914,42 @@ pub async fn save<T: Object>(ctx: Context<'_, DataType<T>, ErrType>) -> Result<(
 pub async fn maj<T: Object>(ctx: Context<'_, DataType<T>, ErrType>) -> Result<(), ErrType> {
     ctx.defer().await?;
     let taille_ancienne = ctx.data().lock().await.database.len();
-    T::maj_rss(ctx.data()).await?;
+    T::maj_rss(ctx.data()).instrument(info_span!("maj_rss")).await?;
     if taille_ancienne != ctx.data().lock().await.database.len() {
         ctx.data().lock().await.update_affichans(&ctx.serenity_context()).await?;
     }
-    ctx.say("Mise à jour effectuée !").await?;
+    let message = ctx.data().lock().await.tr("maj.ok", &[]);
+    ctx.say(message).await?;
     Ok(())
 }
 
 
+/// Cette commande ré-enregistre les commandes du bot auprès de Discord, sans l’éteindre.
+///
+/// L’enregistrement est global. Utile en développement, après l’ajout ou la modification de
+/// commandes. Le nombre de commandes enregistrées est indiqué dans la réponse.
+///
+/// Elle n'est accessible qu'aux utilisateurs qui gèrent le bot, définis par [`Bot::owners`].
+#[poise::command(slash_command, category = "Base de données", owners_only)]
+pub async fn reload_commands<T: Object>(ctx: Context<'_, DataType<T>, ErrType>) -> Result<(), ErrType> {
+    ctx.defer().await?;
+    let commands = &ctx.framework().options().commands;
+    poise::builtins::register_globally(ctx.serenity_context(), commands).await?;
+    let nombre = poise::builtins::create_application_commands(commands).len();
+    info!("{nombre} commandes ré-enregistrées.");
+    let bot = &mut ctx.data().lock().await;
+    ctx.say(bot.tr("reload_commands.ok", &[("nombre", &nombre.to_string())])).await?;
+    bot.log(&ctx, format!("{} a ré-enregistré les commandes du bot.", user_desc(ctx.author()))).await?;
+    Ok(())
+}
+
 /// Cette commande supprime tous les enregistrements des commandes Discord et éteint le bot.
 ///
+/// L’extinction est faite proprement par [`Bot::shutdown`] : arrêt du thread RSS, dernière
+/// sauvegarde puis fermeture de la connexion à Discord.
+///
 /// Elle n'est accessible qu'aux utilisateurs qui gèrent le bot, définis par [`Bot::owners`].
-#[poise::command(slash_command, owners_only)]
+#[poise::command(slash_command, category = "Base de données", owners_only)]
 pub async fn delete_commands<T: Object>(ctx: Context<'_, DataType<T>, ErrType>) -> Result<(), ErrType> {
     let serenity_ctx = ctx.serenity_context();
     ctx.defer().await?;
@@ -247,7 +957,7 @@ pub async fn delete_commands<T: Object>(ctx: Context<'_, DataType<T>, ErrType>)
     let global_commands = serenity_ctx.http.get_global_commands().await?;
 
     try_join_all(global_commands.into_iter().map(|command| {
-        println!("Suppression de la commande {}", command.name);
+        info!("Suppression de la commande {}", command.name);
         serenity_ctx.http.delete_global_command(command.id)
     })).await?;
 
@@ -257,20 +967,133 @@ pub async fn delete_commands<T: Object>(ctx: Context<'_, DataType<T>, ErrType>)
         try_join_all(
             guilds.into_iter().map(|guild| serenity_ctx.http.get_guild_commands(guild.id))
         ).await?.concat().into_iter().map(|guild_command| {
-            println!("Suppression de la commande de serveur {}", guild_command.name);
+            info!("Suppression de la commande de serveur {}", guild_command.name);
             serenity_ctx.http.delete_guild_command(guild_command.guild_id.unwrap(), guild_command.id)
         })
     ).await?;
 
-    ctx.say("Commandes du bot supprimées. Le bot va désormais s’éteindre.").await?;
-    panic!("Commande delete_commands terminée.")
+    let message = ctx.data().lock().await.tr("delete_commands.ok", &[]);
+    ctx.say(message).await?;
+    Bot::shutdown(ctx.data()).await
+}
+
+/// Définit le salon absolu du nom donné, sans redémarrer le bot (voir [`Bot::set_absolute_chan`]).
+///
+/// La modification n’est pas sauvegardée et est perdue au redémarrage du bot.
+///
+/// Elle n'est accessible qu'aux utilisateurs qui gèrent le bot, définis par [`Bot::owners`].
+#[poise::command(slash_command, category = "Salons d’affichage", owners_only)]
+pub async fn salon_absolu<T: Object>(ctx: Context<'_, DataType<T>, ErrType>,
+    #[description = "Nom du salon absolu"] nom: String,
+    #[description = "Salon à utiliser"] salon: serenity::Channel) -> Result<(), ErrType> {
+    let bot = &mut ctx.data().lock().await;
+    bot.set_absolute_chan(nom.as_str(), salon.id(), ctx.serenity_context()).await?;
+    ctx.say(bot.tr("salon_absolu.ok", &[("nom", &nom), ("salon", &salon.to_string())])).await?;
+    bot.log(&ctx, format!("{} a défini le salon absolu {nom} sur {salon}.", user_desc(ctx.author()))).await?;
+    Ok(())
+}
+
+/// Change le salon des logs, sans redémarrer le bot (voir [`Bot::set_log_chan`]).
+///
+/// La modification n’est pas sauvegardée et est perdue au redémarrage du bot.
+///
+/// Elle n'est accessible qu'aux utilisateurs qui gèrent le bot, définis par [`Bot::owners`].
+#[poise::command(slash_command, category = "Salons d’affichage", owners_only)]
+pub async fn set_log<T: Object>(ctx: Context<'_, DataType<T>, ErrType>,
+    #[description = "Nouveau salon des logs"] salon: serenity::Channel) -> Result<(), ErrType> {
+    let bot = &mut ctx.data().lock().await;
+    bot.set_log_chan(salon.id(), ctx.serenity_context()).await?;
+    ctx.say(bot.tr("set_log.ok", &[("salon", &salon.to_string())])).await?;
+    bot.log(&ctx, format!("{} a défini le salon des logs sur {salon}.", user_desc(ctx.author()))).await?;
+    Ok(())
+}
+
+/// Désactive le salon des logs, sans redémarrer le bot (voir [`Bot::unset_log`]).
+///
+/// La modification n’est pas sauvegardée et est perdue au redémarrage du bot.
+///
+/// Elle n'est accessible qu'aux utilisateurs qui gèrent le bot, définis par [`Bot::owners`].
+#[poise::command(slash_command, category = "Salons d’affichage", owners_only)]
+pub async fn unset_log<T: Object>(ctx: Context<'_, DataType<T>, ErrType>) -> Result<(), ErrType> {
+    let bot = &mut ctx.data().lock().await;
+    bot.log(&ctx, format!("{} a désactivé le salon des logs.", user_desc(ctx.author()))).await?;
+    bot.unset_log();
+    ctx.say(bot.tr("unset_log.ok", &[])).await?;
+    Ok(())
 }
 
+/// Restaure la base de données depuis la sauvegarde de secours, après confirmation.
+///
+/// La sauvegarde de secours est celle conservée par le stockage (voir
+/// [`crate::storage::Storage::backup`]), par défaut le fichier `.bak` créé par [`Bot::save`]. La
+/// base actuelle devient alors la nouvelle sauvegarde de secours. La commande n’est pas disponible
+/// pour un stockage ne conservant pas de sauvegarde de secours, comme `SqliteStorage`.
+///
+/// L’historique des modifications est vidé, et les objets ajoutés, modifiés ou supprimés par la
+/// restauration sont signalés aux récepteurs de [`Bot::subscribe`]. Les dates de modification des
+/// objets restaurés sont celles de la sauvegarde.
+///
+/// Elle n'est accessible qu'aux utilisateurs qui gèrent le bot, définis par [`Bot::owners`].
+#[poise::command(slash_command, category = "Base de données", owners_only)]
+pub async fn restaurer_backup<T: Object>(ctx: Context<'_, DataType<T>, ErrType>) -> Result<(), ErrType> {
+    let (backup, question) = {
+        let bot = ctx.data().lock().await;
+        let Some(backup) = bot._backup_storage() else {
+            return Err(ErrType::CommandUseError(bot.tr("restaurer_backup.non_supporte", &[])));
+        };
+        match Bot::<T>::_load_backup(&*backup) {
+            Ok(backup) => {
+                let question = bot.tr("restaurer_backup.question", &[("nombre", &backup.0.len().to_string())]);
+                (backup, question)
+            }
+            Err(e) => {
+                ctx.say(bot.tr("restaurer_backup.erreur", &[("erreur", &e.to_string())])).await?;
+                return Ok(());
+            }
+        }
+    };
+    let (database, last_update) = backup;
+    let taille = database.len();
+
+    if !tools::confirm(&ctx, question).await? {
+        return Ok(());
+    }
+
+    let bot = &mut ctx.data().lock().await;
+    let ancienne = std::mem::replace(&mut bot.database, database);
+    bot.rebuild_search_index();
+    bot.last_rss_update = last_update;
+    /* L’historique porte sur l’ancienne base et ne peut plus être annulé */
+    bot.clear_history();
+    /* Les objets restaurés sont marqués modifiés sans passer par Bot::mark_modified, pour
+       conserver leurs dates de modification sauvegardées */
+    let mut changements = Vec::new();
+    for (id, object) in bot.database.iter_mut() {
+        match ancienne.get(id) {
+            None => changements.push(ChangeEvent::Created(*id)),
+            Some(actuel) if actuel != object => changements.push(ChangeEvent::Modified(*id)),
+            Some(_) => continue
+        }
+        object.set_modified(true);
+    }
+    changements.extend(ancienne.keys().filter(|id| !bot.database.contains_key(id))
+        .map(|id| ChangeEvent::Deleted(*id)));
+    changements.into_iter().for_each(|changement| bot.notify_change(changement));
+    bot.request_affichan_update();
+    bot.save()?;
+    bot.log(&ctx, format!("{} a restauré la base de données depuis la sauvegarde de secours ({taille} objets).",
+        user_desc(ctx.author()))).await?;
+    bot._update_affichans_after_change(ctx.serenity_context(), None).await?;
+    ctx.say(bot.tr("restaurer_backup.ok", &[])).await?;
+    Ok(())
+}
 
 
 /// Enregistrement des commandes par défaut de la bibliothèque fondabots.
 pub fn command_list<T: Object>() -> Vec<Command<DataType<T>, ErrType>> {
-    vec![rechercher(), plop(), supprimer(), annuler(), update_affichans(), renommer(), doublons(),
-         up(), refresh_affichans(), bdd(), taille_bdd(), save(), maj(),
-        alias("search", rechercher()), delete_commands(), reset_affichans()]
+    let mut commands = vec![rechercher(), recherche_avancee(), objet(), chercher_id_par_message(), lister_tout(), aide(), plop(), ping(), supprimer(), supprimer_lot(), annuler(), annuler_apercu(), vider_historique(), update_affichans(), renommer(), cloner(), qui_a_modifie(), doublons(),
+         up(), epingler(), resync(), nettoyer_salon(), orphelins(), modifies_en_attente(), refresh_affichans(), bdd(), taille_bdd(), stats(), rss_status(), save(), maj(),
+        reload_commands(), delete_commands(), salon_absolu(), set_log(), unset_log(), reset_affichans(), restaurer_backup(), purger_anciens(), deplacer(), export_affichan(), contenu_salon()];
+    commands.extend(aliases(&["search"], rechercher));
+    commands
 }
\ No newline at end of file
@@ -49,7 +49,7 @@ impl Display for Error {
             Error::YamlParseError(e) => write!(f, "Erreur de formatage yaml : {e}"),
             Error::InteractionIDError(id, message) => write!(f, "Erreur de format de l’identifiant {id} sur le message {message}"),
             Error::NoneError => write!(f, "Option None non-attendue."),
-            Error::UnloadedItem(id) => write!(f, "Affichan {id} appelé mais non chargé."),
+            Error::UnloadedItem(id) => write!(f, "Objet Discord {id} appelé mais non chargé."),
             Error::Generic => write!(f, "Erreur de bot générique."),
             Error::CommandUseError(e) => write!(f, "Erreur d’utilisation de la commande : {e}"),
             Error::LibError(e) => Display::fmt(&e, f)
@@ -12,12 +12,11 @@
 use std::collections::{HashMap, HashSet};
 
 use poise::{Context, CreateReply};
-use serenity::all::{CreateEmbed, CreateEmbedAuthor, Timestamp};
+use serenity::all::{CreateEmbedAuthor, Timestamp};
 
-use crate::object::Field;
-use crate::object::Object;
+use crate::object::{Field, Object, RangeField};
 use crate::tools::get_object;
-use crate::{tools, DataType, ErrType};
+use crate::{tools, Bot, DataType, ErrType};
 
 /* Fonction auxiliaire renvoyant tous les objets ayant le champ demandé à la valeur demandée */
 fn _lister_one<'a, T: Object, E: Field<T>>(database: &'a HashMap<u64, T>, field: &Option<E>) -> HashSet<&'a u64> {
@@ -25,6 +24,50 @@ fn _lister_one<'a, T: Object, E: Field<T>>(database: &'a HashMap<u64, T>, field:
         .into_iter().map(|(id, _) | {id}).collect()
 }
 
+/* Fonction auxiliaire affichant les objets donnés sous forme de liste paginée, ou l’embed
+ * « Aucun résultat » si la liste est vide. `recherche` est la description de la recherche
+ * affichée en auteur de l’embed. Le verrou sur les données du bot ne doit pas être tenu : il
+ * n’est pris que pour construire les embeds, et relâché pendant l’envoi. */
+pub(crate) async fn _afficher_resultats<T: Object>(
+    ctx: Context<'_, DataType<T>, ErrType>,
+    objects: Vec<u64>,
+    recherche: String
+) -> Result<(), ErrType> {
+    let embeds = {
+        let bot = ctx.data().lock().await;
+        let database = &bot.database;
+        let messages = tools::create_paged_list(
+            objects.iter().filter(|object| database.contains_key(object)).collect(),
+            |object| tools::list_entry(database.get(object).unwrap()),
+            1000
+        );
+
+        if messages.is_empty() {
+            vec![bot.get_theme().error_embed()
+                .title(bot.tr("recherche.aucun_resultat", &[]))
+                .author(CreateEmbedAuthor::new(bot.tr("recherche.auteur", &[("recherche", &recherche)])))
+                .timestamp(Timestamp::now())]
+        } else {
+            let template = bot.get_theme().neutral_embed()
+                .author(CreateEmbedAuthor::new(bot.tr("recherche.auteur", &[("recherche", &recherche)])))
+                .title(bot.tr("recherche.titre", &[]))
+                .timestamp(Timestamp::now());
+            bot.get_theme().multimessages(messages, template)
+        }
+    };
+    Bot::send_embed_unlocked(&ctx, embeds).await
+}
+
+/* Affichage d’un champ optionnel dans la description d’une recherche */
+fn _field_desc<T: Object, E: Field<T>>(bot: &Bot<T>, field: &Option<E>) -> String {
+    if let Some(f) = field {f.to_string()} else {bot.tr("recherche.tous", &[])}
+}
+
+/* Erreur d’utilisation renvoyée lorsqu’aucun paramètre d’une commande lister n’est spécifié */
+async fn _parametre_requis<T: Object>(ctx: Context<'_, DataType<T>, ErrType>, key: &str) -> ErrType {
+    ErrType::CommandUseError(ctx.data().lock().await.tr(key, &[]))
+}
+
 /// Auxiliaire générique pour une commande lister à deux champs. Effectue une recherche parmi la
 /// base de données et affiche les résultats. Si l’une des entrées est définie à [`None`], alors
 /// la recherche acceptera tout type de champs.
@@ -36,36 +79,133 @@ pub async fn lister_two<T: Object, E1: Field<T>, E2: Field<T>>(
     field2: Option<E2>
 ) -> Result<(), ErrType> {
     if field1.is_none() && field2.is_none() {
-        Err(ErrType::CommandUseError("au moins l’un des deux paramètres doit être spécifié.".to_string()))?;
+        Err(_parametre_requis(ctx, "recherche.parametre_requis").await)?;
     }
-    let bot = &mut ctx.data().lock().await;
-    let database = &bot.database;
-
-    let messages = tools::create_paged_list(
-        _lister_one(database, &field1).intersection(&_lister_one(database, &field2)).collect(),
-        |object| database.get(object).unwrap().get_list_entry(),
-        1000
-    );
-
-    if messages.is_empty() {
-        ctx.send(CreateReply::default().embed(CreateEmbed::new()
-            .title("Aucun résultat.")
-            .color(16001600)
-            .author(CreateEmbedAuthor::new(format!("Recherche : {} – {}",
-                                                   if let Some(s) = field1 {s.to_string()} else {"Tous".to_string()},
-                                                   if let Some(t) = field2 {t.to_string()} else {"Tous".to_string()})))
-            .timestamp(Timestamp::now()))).await?;
-    } else {
-        bot.send_embed(&ctx, tools::get_multimessages(messages, CreateEmbed::new()
-            .author(CreateEmbedAuthor::new(format!("Recherche : {} – {}",
-                                                   if let Some(s) = field1 {s.to_string()} else {"Tous".to_string()},
-                                                   if let Some(t) = field2 {t.to_string()} else {"Tous".to_string()}
-            )))
-            .title("Résultats de la recherche")
-            .timestamp(Timestamp::now())
-            .color(73887))).await?;
+    let (objects, recherche) = {
+        let bot = ctx.data().lock().await;
+        let database = &bot.database;
+        let objects: Vec<u64> = _lister_one(database, &field1).intersection(&_lister_one(database, &field2))
+            .map(|&&id| id).collect();
+        (objects, format!("{} – {}", _field_desc(&bot, &field1), _field_desc(&bot, &field2)))
+    };
+    _afficher_resultats(ctx, objects, recherche).await
+}
+
+/// Auxiliaire générique pour une commande lister à trois champs, suivant le même fonctionnement
+/// que [`lister_two`]. Si l’une des entrées est définie à [`None`], alors la recherche acceptera
+/// tout type de champs.
+///
+/// La commande échoue (message d’erreur Discord) si les trois champs sont [`None`].
+pub async fn lister_three<T: Object, E1: Field<T>, E2: Field<T>, E3: Field<T>>(
+    ctx: Context<'_, DataType<T>, ErrType>,
+    field1: Option<E1>,
+    field2: Option<E2>,
+    field3: Option<E3>
+) -> Result<(), ErrType> {
+    if field1.is_none() && field2.is_none() && field3.is_none() {
+        Err(_parametre_requis(ctx, "recherche.parametre_requis").await)?;
     }
+    let (objects, recherche) = {
+        let bot = ctx.data().lock().await;
+        let database = &bot.database;
+        let objects3 = _lister_one(database, &field3);
+        let objects: Vec<u64> = _lister_one(database, &field1).intersection(&_lister_one(database, &field2))
+            .filter(|id| objects3.contains(*id))
+            .map(|&&id| id).collect();
+        (objects, format!("{} – {} – {}", _field_desc(&bot, &field1), _field_desc(&bot, &field2), _field_desc(&bot, &field3)))
+    };
+    _afficher_resultats(ctx, objects, recherche).await
+}
+
+/// Prédicat sur un [`Object`] utilisé par [`lister_filtered`].
+pub type Filter<T> = Box<dyn Fn(&T) -> bool + Send + Sync>;
+
+/// Construit un [`Filter`] à partir d’un [`Field`] optionnel, d’après [`Field::comply_with`].
+/// Renvoie [`None`] si le champ est [`None`], afin que [`lister_filtered`] puisse détecter
+/// l’absence de tout filtre.
+pub fn field_filter<T: Object, F: Field<T> + 'static>(field: Option<F>) -> Option<Filter<T>> {
+    field.map(|field| -> Filter<T> { Box::new(move |object| F::comply_with(object, &Some(field.clone()))) })
+}
+
+/// Auxiliaire générique pour une commande lister à un nombre quelconque de critères. Les objets
+/// affichés sont ceux qui respectent tous les filtres donnés, triés par date.
+///
+/// Les filtres peuvent être construits à partir de [`Field`] optionnels par [`field_filter`],
+/// les [`None`] étant alors retirés du vecteur. Si le vecteur de filtres est vide, tous les objets
+/// sont affichés, sauf si `filtre_requis` est `true`, auquel cas la commande échoue (message
+/// d’erreur Discord). `title` est la description de la recherche affichée dans les résultats.
+pub async fn lister_filtered<T: Object>(
+    ctx: Context<'_, DataType<T>, ErrType>,
+    filters: Vec<Filter<T>>,
+    title: String,
+    filtre_requis: bool
+) -> Result<(), ErrType> {
+    if filters.is_empty() && filtre_requis {
+        Err(_parametre_requis(ctx, "recherche.parametre_requis").await)?;
+    }
+    let objects: Vec<u64> = {
+        let bot = ctx.data().lock().await;
+        tools::sort_by_date(bot.database.iter()
+            .filter(|(_, object)| filters.iter().all(|filter| filter(object)))
+            .collect())
+            .into_iter().map(|(&id, _)| id).collect()
+    };
+    _afficher_resultats(ctx, objects, title).await
+}
+
+/// Auxiliaire générique pour une commande lister selon un intervalle sur un champ numérique
+/// [`RangeField`]. Les bornes sont incluses ; une borne à [`None`] n’est pas prise en compte.
+///
+/// La commande échoue (message d’erreur Discord) si les deux bornes sont [`None`].
+pub async fn lister_range<T: Object, R: RangeField<T>>(
+    ctx: Context<'_, DataType<T>, ErrType>,
+    min: Option<i64>,
+    max: Option<i64>
+) -> Result<(), ErrType> {
+    if min.is_none() && max.is_none() {
+        Err(_parametre_requis(ctx, "recherche.borne_requise").await)?;
+    }
+    let recherche = format!("{} : {} – {}", R::field_name(),
+                            min.map_or("…".to_string(), |min| min.to_string()),
+                            max.map_or("…".to_string(), |max| max.to_string()));
+    lister_filtered(ctx, vec![Box::new(move |object| R::in_range(object, min, max))], recherche, true).await
+}
 
+/// Auxiliaire générique combinant la recherche par nom (voir [`Bot::search`]) et le filtrage
+/// par un champ [`Field`]. Seuls les objets répondant aux deux critères sont affichés.
+pub async fn rechercher_avec_filtre<T: Object, F: Field<T>>(
+    ctx: Context<'_, DataType<T>, ErrType>,
+    critere: String,
+    field: Option<F>
+) -> Result<(), ErrType> {
+    let (objects, recherche) = {
+        let bot = ctx.data().lock().await;
+        let filtres = _lister_one(&bot.database, &field);
+        let objects: Vec<u64> = bot.search(critere.as_str()).into_iter()
+            .filter(|id| filtres.contains(id))
+            .copied().collect();
+        (objects, format!("{critere} – {}", _field_desc(&bot, &field)))
+    };
+    _afficher_resultats(ctx, objects, recherche).await
+}
+
+/// Auxiliaire générique pour une commande de comptage. Affiche uniquement le nombre d’objets
+/// correspondant au champ demandé, sans les lister. Si le champ est [`None`], le comportement
+/// est celui défini par [`Field::comply_with`].
+pub async fn compter<T: Object, F: Field<T>>(
+    ctx: Context<'_, DataType<T>, ErrType>,
+    field: Option<F>
+) -> Result<(), ErrType> {
+    let (message, ephemere) = {
+        let bot = ctx.data().lock().await;
+        let nombre = _lister_one(&bot.database, &field).len();
+        (bot.tr(if nombre > 1 {"recherche.compte.plusieurs"} else {"recherche.compte.un"}, &[
+            ("nombre", &nombre.to_string()),
+            ("champ", F::field_name()),
+            ("valeur", &_field_desc(&bot, &field))
+        ]), bot.is_ephemeral_reply(&ctx))
+    };
+    ctx.send(CreateReply::default().content(message).ephemeral(ephemere)).await?;
     Ok(())
 }
 
@@ -76,18 +216,107 @@ pub async fn change_field<T: Object, F: Field<T>>(ctx: Context<'_, DataType<T>,
     let bot = &mut ctx.data().lock().await;
     if let Some(object_id) = get_object(&ctx, bot, &critere).await? {
         bot.archive(vec![object_id]);
+        let avant = bot.database.get(&object_id).unwrap().clone();
+        F::set_for(bot.database.get_mut(&object_id).unwrap(), &field);
+        bot.mark_modified_by(object_id, ctx.author().id);
         let object = bot.database.get(&object_id).unwrap();
-        ctx.say(format!("{} de « {} » changé pour « {field} »", F::field_name() ,object.get_name())).await?;
-        bot.log(&ctx, format!("{} a changé la propriété {} de l'objet {} (id: {}) pour {}.",
+        ctx.say(bot.tr("champ.change", &[
+            ("champ", F::field_name()),
+            ("nom", object.get_name()),
+            ("valeur", &field.to_string())
+        ])).await?;
+        let details = tools::describe_diff(&avant, object)
+            .map_or_else(String::new, |details| format!("\n{details}"));
+        bot.log(&ctx, format!("{} a changé la propriété {} de l'objet {} (id: {}) pour {}.{details}",
             tools::user_desc(ctx.author()),
             F::field_name(),
             object.get_name(),
             object_id,
             field
         )).await?;
-        let object = bot.database.get_mut(&object_id).unwrap();
-        F::set_for(object, &field);
-        object.set_modified(true);
     }
     Ok(())
-}
\ No newline at end of file
+}
+
+/// Fonction auxiliaire permettant la modification d’un champ [`Field`] donné pour tous les objets
+/// correspondant au critère de recherche (voir [`Bot::search`]), par exemple pour passer tous les
+/// objets d’un état à un autre.
+///
+/// Les objets sont obtenus par [`tools::get_objects`] : une confirmation est demandée si plus de
+/// `seuil_confirmation` objets sont concernés. La modification est annulable en une fois par la
+/// commande annuler.
+pub async fn change_field_bulk<T: Object, F: Field<T>>(ctx: Context<'_, DataType<T>, ErrType>,
+                    critere: String,
+                    field: F,
+                    seuil_confirmation: usize) -> Result<(), ErrType> {
+    let question = |bot: &Bot<T>, nombre: usize| bot.tr("champ.lot.question", &[
+        ("champ", F::field_name()),
+        ("valeur", &field.to_string()),
+        ("nombre", &nombre.to_string()),
+        ("critere", &critere)
+    ]);
+    let objects = tools::get_objects(&ctx, &critere, seuil_confirmation, question).await?;
+    if objects.is_empty() {
+        return Ok(());
+    }
+
+    let bot = &mut ctx.data().lock().await;
+    /* Des objets ont pu être supprimés pendant la confirmation */
+    let objects: Vec<u64> = objects.into_iter().filter(|object_id| bot.database.contains_key(object_id)).collect();
+    bot.archive(objects.clone());
+    let mut details = String::new();
+    for &object_id in &objects {
+        let avant = bot.database.get(&object_id).unwrap().clone();
+        F::set_for(bot.database.get_mut(&object_id).unwrap(), &field);
+        bot.mark_modified_by(object_id, ctx.author().id);
+        let object = bot.database.get(&object_id).unwrap();
+        if let Some(diff) = tools::describe_diff(&avant, object) {
+            details += &format!("\n{} (id: {object_id}) :\n{diff}", object.get_name());
+        }
+    }
+    ctx.say(bot.tr("champ.lot.ok", &[
+        ("champ", F::field_name()),
+        ("nombre", &objects.len().to_string()),
+        ("valeur", &field.to_string())
+    ])).await?;
+    bot.log(&ctx, format!("{} a changé la propriété {} pour {} des {} objets correspondant à la recherche « {critere} ».{details}",
+        tools::user_desc(ctx.author()),
+        F::field_name(),
+        field,
+        objects.len()
+    )).await?;
+    Ok(())
+}
+
+/// Fonction auxiliaire permettant de remettre un champ [`Field`] donné à sa valeur neutre,
+/// définie par [`Field::default_value`]. Si le champ n’a pas de valeur neutre, une erreur est
+/// signalée à l’utilisateur.
+pub async fn clear_field<T: Object, F: Field<T>>(ctx: Context<'_, DataType<T>, ErrType>,
+                    critere: String) -> Result<(), ErrType> {
+    let bot = &mut ctx.data().lock().await;
+    let Some(field) = F::default_value() else {
+        return Err(ErrType::CommandUseError(bot.tr("champ.non_effacable", &[("champ", F::field_name())])));
+    };
+    if let Some(object_id) = get_object(&ctx, bot, &critere).await? {
+        bot.archive(vec![object_id]);
+        let avant = bot.database.get(&object_id).unwrap().clone();
+        F::set_for(bot.database.get_mut(&object_id).unwrap(), &field);
+        bot.mark_modified_by(object_id, ctx.author().id);
+        let object = bot.database.get(&object_id).unwrap();
+        ctx.say(bot.tr("champ.remis", &[
+            ("champ", F::field_name()),
+            ("nom", object.get_name()),
+            ("valeur", &field.to_string())
+        ])).await?;
+        let details = tools::describe_diff(&avant, object)
+            .map_or_else(String::new, |details| format!("\n{details}"));
+        bot.log(&ctx, format!("{} a remis la propriété {} de l'objet {} (id: {}) à {}.{details}",
+            tools::user_desc(ctx.author()),
+            F::field_name(),
+            object.get_name(),
+            object_id,
+            field
+        )).await?;
+    }
+    Ok(())
+}
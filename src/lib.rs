@@ -27,31 +27,41 @@
 
 use poise::serenity_prelude as serenity;
 use std::collections::VecDeque;
-use std::collections::{HashMap, HashSet};
-use std::fs;
-use std::sync::Arc;
+use std::collections::{BinaryHeap, HashMap, HashSet};
+#[cfg(feature = "metrics")]
+use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+use std::sync::{Arc, Mutex as StdMutex};
 use std::time::{Duration, SystemTime};
 
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, TimeDelta, Utc};
 use poise::futures_util::FutureExt;
 use poise::reply::CreateReply;
 use poise::Context;
 use poise::Framework;
 use serenity::all::{ActivityData, ChannelId, UserId};
 use serenity::all::{ButtonStyle, Context as SerenityContext, CreateInteractionResponse, CreateInteractionResponseMessage, GuildChannel, MessageId};
-use serenity::all::{ComponentInteraction, CreateButton, GatewayIntents};
-use serenity::all::{CreateActionRow, EditMessage, Interaction};
+use serenity::all::{ActionRowComponent, CommandInteraction, ComponentInteraction, CreateButton, CreateInteractionResponseFollowup, GatewayIntents, ModalInteraction};
+use serenity::all::{CreateActionRow, CreateMessage, EditInteractionResponse, EditMessage, Interaction, MessageUpdateEvent, Reaction, Timestamp};
 use serenity::client::ClientBuilder;
-use serenity::futures::future::try_join_all;
+use serenity::futures::future::{join_all, try_join_all};
+use serenity::gateway::ShardManager;
 use serenity::prelude::*;
-use serenity::CreateEmbed;
+use serenity::{CreateEmbed, CreateEmbedFooter};
 use serenity::FullEvent;
+use tokio::sync::{broadcast, Notify};
+use tokio::task::JoinHandle;
 use tokio::time;
-use yaml_rust2::{yaml, Yaml, YamlEmitter, YamlLoader};
+use tokio_util::sync::CancellationToken;
+use tracing::{error, info, info_span, instrument, warn, Instrument};
+use yaml_rust2::{yaml, Yaml, YamlEmitter};
 
-use crate::command_data::CommandChecker;
-use crate::tools::{basicize, Preloaded, PreloadedChannel};
+use crate::command_data::{CommandChecker, CommandData, MemberPermissions, Permission, PermissionChecker};
+use crate::tools::{basicize_with, NormalizeOptions, Preloaded, PreloadedChannel};
 use affichan::Affichan;
+use locale::Locale;
+use object::Timestamped;
+use search::{ScoredResult, SearchIndex};
+use storage::{Storage, StorageState, YamlFileStorage};
 /// Type d’erreur utilisé par la bibliothèque fondabots. Renommé ici pour permettre un
 /// changement rapide si besoin et l’évitement d’une confusion avec d’autres types d’erreurs.
 pub use errors::Error as ErrType;
@@ -63,6 +73,12 @@ pub use object::Object;
 /// incohérences de versions).
 pub use yaml_rust2;
 
+/// Réutilisation de tracing, utilisé pour les journaux de la bibliothèque (notamment par la macro
+/// [`try_loop`]). L’initialisation d’un subscriber (par exemple avec `tracing-subscriber`, filtrable
+/// par la variable d’environnement `RUST_LOG`) est laissée au bot utilisant la bibliothèque ; sans
+/// subscriber, aucun journal n’est affiché.
+pub use tracing;
+
 
 pub mod command_data;
 pub mod affichan;
@@ -71,6 +87,13 @@ pub mod errors;
 pub mod tools;
 pub mod generic_commands;
 pub mod object;
+pub mod locale;
+mod search;
+pub mod storage;
+#[cfg(feature = "metrics")]
+pub mod metrics;
+#[cfg(feature = "test-utils")]
+pub mod testing;
 
 
 /// Redéfinition du type utilisé pour des données de [`poise`], utilisant un [`Arc`] et un [`Mutex`]
@@ -78,14 +101,192 @@ pub mod object;
 ///
 /// `T` doit implémenter [`Object`] : il faut garder en tête que ce type n’est qu’un
 /// raccourci vers [`Bot`] qui impose `T: Object`.
+///
+/// Le verrou étant exclusif, une commande le tenant bloque toutes les autres ainsi que le thread
+/// RSS : il est préférable de le relâcher avant les appels à Discord lorsque c’est possible (voir
+/// par exemple [`Bot::send_embed_unlocked`]).
+/* TODO 2.0 : séparer la base de données derrière un RwLock (ou arc_swap) pour permettre les
+   lectures concurrentes, l’écriture restant exclusive. Cela change DataType et tous les appels
+   à lock(), d’où le report à une version majeure. */
 pub type DataType<T> = Arc<Mutex<Bot<T>>>;
 
+/// Élément de l’aperçu d’une annulation renvoyé par [`Bot::undo_preview`] : identifiant de l’objet,
+/// état actuel et état restauré.
+pub type UndoPreview<'a, T> = (u64, Option<&'a T>, Option<&'a T>);
+
+/// Fonction de traitement des évènements Discord, appelée avant le traitement de l’évènement par
+/// la bibliothèque (voir [`Bot::add_event_handler`]).
+///
+/// Elle prend en paramètres le contexte serenity, l’évènement et les données du bot (non
+/// verrouillées), et renvoie un bloc async boxé renvoyant `true` si l’évènement doit continuer
+/// d’être traité.
+///
+/// Note : il faut utiliser [`FutureExt::boxed`] après le bloc async.
+pub type EventHandler<T> = dyn for<'a> Fn(&'a SerenityContext, &'a FullEvent, &'a DataType<T>) -> poise::BoxFuture<'a, Result<bool, ErrType>> + Send + Sync;
+
+/// Fonction calculant le texte de l’activité dynamique du bot (voir [`Bot::dynamic_activity`])
+/// d’après l’état du bot, par exemple le nombre d’objets en attente.
+pub type DynamicActivity<T> = dyn Fn(&Bot<T>) -> String + Send + Sync;
+
+/// Profondeur de l’historique des modifications, c’est-à-dire nombre maximal de modifications
+/// annulables d’affilée (voir [`Bot::archive`] et [`Bot::annuler`]).
+pub const HISTORY_DEPTH: usize = 5;
+
+/// Format des messages envoyés dans le salon des logs par [`Bot::log`], à définir par
+/// [`Bot::log_style`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum LogStyle {
+    /// Message texte brut (par défaut).
+    #[default]
+    Text,
+    /// Embed contenant le texte du log en description, avec une couleur et l’heure du log.
+    Embed
+}
+
+/// Politique de démarrage du bot, à définir par [`Bot::startup_policy`] : décide si une erreur de
+/// chargement d’un élément lors de [`Bot::setup`] (salon d’affichage, salon absolu ou salon des
+/// logs) arrête le bot ou est seulement loggée.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum StartupPolicy {
+    /// Toute erreur de chargement est loggée puis arrête le bot, qui se déconnecte de Discord.
+    /// Adaptée à la production, où une configuration invalide doit être corrigée immédiatement.
+    Strict,
+    /// Les erreurs de chargement sont loggées et le bot démarre sans les éléments concernés.
+    /// Adaptée au développement.
+    Tolerant
+}
+
+/// Mode de comparaison des noms pour la détection des doublons par [`Bot::find_duplicates`], à
+/// définir par [`Bot::duplicate_matching`] (voir [`tools::duplicate_key`]).
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum DuplicateMatching {
+    /// Noms comparés après normalisation (casse, diacritiques, espaces) selon les options de
+    /// [`Bot::normalize_options`] (par défaut).
+    #[default]
+    Normalized,
+    /// Noms comparés exactement, pour les bots dont les objets peuvent ne différer que par la
+    /// casse ou la ponctuation de leur nom.
+    Exact
+}
+
+/// Thème des embeds envoyés par la bibliothèque (résultats de recherche, statistiques, logs…), à
+/// définir par [`Bot::theme`].
+///
+/// Le thème par défaut reprend les couleurs historiques de la bibliothèque, sans pied de page.
+/// Pour ne changer que certains éléments, utiliser `Theme { error_color: 0xff0000, ..Theme::default() }`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Theme {
+    /// Couleur des embeds signalant une réussite.
+    pub success_color: u32,
+    /// Couleur des embeds signalant une erreur ou l’absence de résultat.
+    pub error_color: u32,
+    /// Couleur des autres embeds (listes, statistiques, logs…).
+    pub neutral_color: u32,
+    /// Texte du pied de page des embeds d’une seule page. Si [`None`], ces embeds n’ont pas de
+    /// pied de page.
+    pub footer_text: Option<String>,
+    /// URL de l’icône des pieds de page, y compris ceux des listes paginées.
+    pub footer_icon: Option<String>
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Self {
+            success_color: 73887,
+            error_color: 16001600,
+            neutral_color: 73887,
+            footer_text: None,
+            footer_icon: None
+        }
+    }
+}
+
+impl Theme {
+    /// Renvoie un embed vide de la couleur de réussite, avec le pied de page du thème.
+    pub fn success_embed(&self) -> CreateEmbed {
+        self._embed(self.success_color)
+    }
+
+    /// Renvoie un embed vide de la couleur d’erreur, avec le pied de page du thème.
+    pub fn error_embed(&self) -> CreateEmbed {
+        self._embed(self.error_color)
+    }
+
+    /// Renvoie un embed vide de la couleur neutre, avec le pied de page du thème.
+    pub fn neutral_embed(&self) -> CreateEmbed {
+        self._embed(self.neutral_color)
+    }
+
+    /// Renvoie un pied de page contenant le texte donné et l’icône du thème.
+    pub fn footer(&self, text: impl Into<String>) -> CreateEmbedFooter {
+        let footer = CreateEmbedFooter::new(text);
+        match &self.footer_icon {
+            Some(icon) => footer.icon_url(icon),
+            None => footer
+        }
+    }
+
+    /// Équivalent de [`tools::object_embed`] dont le pied de page porte l’icône du thème. Le texte
+    /// du pied de page reste l’identifiant de l’objet, quel que soit [`Theme::footer_text`].
+    pub fn object_embed(&self, id: u64, title: impl Into<String>, color: u32) -> CreateEmbed {
+        tools::object_embed(id, title, color).footer(self.footer(id.to_string()))
+    }
+
+    /// Équivalent de [`tools::get_multimessages`] dont les pieds de page portent l’icône du thème.
+    pub fn multimessages(&self, pages: Vec<String>, template: CreateEmbed) -> Vec<CreateEmbed> {
+        self._paginate(tools::get_multimessages(pages, template))
+    }
+
+    /// Équivalent de [`tools::get_multimessages_fields`] dont les pieds de page portent l’icône
+    /// du thème.
+    pub fn multimessages_fields(&self, pages: Vec<Vec<(String, String)>>, template: CreateEmbed) -> Vec<CreateEmbed> {
+        self._paginate(tools::get_multimessages_fields(pages, template))
+    }
+
+    /* Embed vide de la couleur donnée, avec le pied de page du thème s’il a un texte */
+    fn _embed(&self, color: u32) -> CreateEmbed {
+        let embed = CreateEmbed::new().color(color);
+        match &self.footer_text {
+            Some(text) => embed.footer(self.footer(text)),
+            None => embed
+        }
+    }
+
+    /* Remplace les pieds de page « Page x / y » par des pieds de page portant l’icône du thème */
+    fn _paginate(&self, embeds: Vec<CreateEmbed>) -> Vec<CreateEmbed> {
+        if self.footer_icon.is_none() {
+            return embeds;
+        }
+        let total = embeds.len();
+        embeds.into_iter().enumerate()
+            .map(|(i, embed)| embed.footer(self.footer(format!("Page {} / {total}", i + 1))))
+            .collect()
+    }
+}
+
+/// Changement de la base de données, émis par le bot à chaque ajout, modification ou suppression
+/// d’un objet (voir [`Bot::subscribe`]). Chaque variante contient l’identifiant de l’objet concerné.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum ChangeEvent {
+    /// Un objet a été ajouté à la base de données.
+    Created(u64),
+    /// Un objet de la base de données a été modifié.
+    Modified(u64),
+    /// Un objet a été supprimé de la base de données.
+    Deleted(u64)
+}
+
 /// Structure de données de bot. Cette structure, où `T` est l’implémentation d’un [`Object`]
 /// pour le bot souhaité, contient, entre autre, la base de données et les salons d’affichage.
 pub struct Bot<T: Object> {
     /// Base de données des objets.
     ///
     /// Chaque objet doit avoir un identifiant unique qui sera utilisé comme clé dans la [`HashMap`].
+    ///
+    /// Les ajouts, suppressions et renommages faits directement dans cette [`HashMap`] ne sont pris
+    /// en compte par la recherche ([`Bot::search`]) qu’après un appel à [`Bot::mark_modified`] sur
+    /// l’objet concerné ou à [`Bot::rebuild_search_index`]. Préférer [`Bot::insert`] et
+    /// [`Bot::remove`].
     pub database: HashMap<u64, T>,
     /* Historique utilisé dans Bot::archive et Bot::annuler
         Il prend la forme d’une pile de vecteurs représentant une modification, contenant
@@ -94,6 +295,22 @@ pub struct Bot<T: Object> {
         supprimé en cas d’annulation de l’action. */
     history: VecDeque<Vec<(u64, Option<T>)>>,
 
+    /* Message de refus envoyé par CommandData::check, voir Bot::permission_denied_message */
+    pub(crate) permission_denied_message: Option<String>,
+
+    /* Traitements des évènements définis par l’utilisateur, voir Bot::add_event_handler */
+    event_handlers: Vec<Arc<EventHandler<T>>>,
+
+    /* Nombre maximal d’appels simultanés à l’API Discord par salon d’affichage, voir
+       Bot::api_concurrency */
+    api_concurrency: usize,
+
+    /* Journalisation du bilan des mises à jour des salons d’affichage, voir Bot::debug_affichans */
+    debug_affichans: bool,
+
+    /* Nombre maximal d’objets d’une entrée de l’historique, voir Bot::history_max_objects */
+    history_max_objects: usize,
+
     /// Date et heure du dernier écrit récupéré dans les flux RSS. Ce champ est à réutiliser dans
     /// [`Object::maj_rss`] pour éviter de récupérer plusieurs fois le même écrit.
     pub last_rss_update: DateTime<Utc>,
@@ -101,26 +318,38 @@ pub struct Bot<T: Object> {
     /* Identifiant du bot. None si le bot n’est pas encore chargé. */
     self_id: Option<UserId>,
 
-    /* Liste des multimessages. L’identifiant est le timestamp de la création des multimessages. */
-    multimessages: HashMap<String, Vec<CreateEmbed>>,
+    /* Multimessages en cours, par identifiant (préfixe des identifiants de leurs boutons, formé
+       du timestamp de leur création). Sauvegardés dans les métadonnées, voir MultiMessage. */
+    multimessages: HashMap<String, MultiMessage>,
+
+    /* Dernier timestamp attribué à un multimessage, voir Bot::_next_multimessage_id. Garantit
+       l’unicité des identifiants de multimessages créés dans la même milliseconde. */
+    last_multimessage_id: u128,
 
-    /* Positions actuelle des multimessages, par la même clé. */
-    mmpositions: HashMap<String, usize>,
+    /* Identifiant du multimessage porté par chaque message Discord, pour retrouver un multimessage
+       d’après le message sur lequel on a cliqué. */
+    mm_messages: HashMap<MessageId, String>,
 
     /* Salons d’affichage */
     affichans: Vec<Affichan<T>>,
 
-    /* Chemin de fichier vers le fichier de sauvegarde */
-    data_file: String,
+    /* Données sauvegardées des salons d’affichage retirés au démarrage faute d’avoir pu être
+       chargés (voir StartupPolicy::Tolerant), par identifiant de salon. Réécrites telles quelles
+       à chaque sauvegarde pour que ces salons retrouvent leurs messages et leurs objets forcés
+       au prochain démarrage où ils se chargent. */
+    unloaded_affichans: HashMap<u64, Yaml>,
 
-    /* Stockage des salons absolus, c’est-à-dire des salons accessibles dans toute commande. */
-    absolute_chans: HashMap<&'static str, GuildChannel>,
+    /* Stockage des salons absolus, c’est-à-dire des salons accessibles dans toute commande.
+       Modifiable à chaud par Bot::set_absolute_chan. Un salon qui n’a pas pu être chargé au
+       démarrage reste non chargé, et est rechargé par Bot::load_absolute_chan. */
+    absolute_chans: HashMap<String, PreloadedChannel>,
 
     /// Trigger permettant la mise à jour des salons d’affichage à la fin du traitement de l’évènement.
     ///
     /// Passer à `true` pour activer la mise à jour (appel à [`Bot::update_affichans`]),
     /// repassera à `false` après. Ce trigger permet de delayer cette mise à jour afin de ne pas
-    /// bloquer le thread et de ne pas utiliser de `await`.
+    /// bloquer le thread et de ne pas utiliser de `await`. Voir aussi
+    /// [`Bot::request_affichan_update`] et [`Bot::affichan_debounce`].
     pub update_affichans: bool,
 
     /// Cette fonction est appelée systématiquement au début de chaque commande intégrée, permettant de
@@ -134,13 +363,241 @@ pub struct Bot<T: Object> {
     ///
     /// La configuration de cette commande doit se faire par [`Bot::command_checker`], et est
     /// optionnelle. Par défaut, elle renvoie toujours `true`.
-    pub(crate) command_checker: Box<CommandChecker<T>>,
+    pub(crate) command_checker: Arc<CommandChecker<T>>,
+
+    /* Vérification sans effet de bord des permissions, utilisée par la commande aide. Voir
+       Bot::permission_checker. */
+    pub(crate) permission_checker: Arc<PermissionChecker<T>>,
+
+    /* Permissions Discord requises pour voir les commandes de chaque niveau de permission. Voir
+       Bot::member_permissions. */
+    member_permissions: Box<MemberPermissions>,
 
     /* Stockage des owners, transféré au Framework */
     owners: HashSet<UserId>,
 
     /* Salon des logs. Si None, aucun log ne sera produit. */
-    log: Option<PreloadedChannel>
+    log: Option<PreloadedChannel>,
+
+    /* Format des logs envoyés par Bot::log */
+    log_style: LogStyle,
+
+    /* Thème des embeds envoyés par la bibliothèque */
+    theme: Theme,
+
+    /* Options de normalisation des chaînes utilisées pour la recherche */
+    normalize_options: NormalizeOptions,
+
+    /* Mode de comparaison des noms pour la détection des doublons */
+    duplicate_matching: DuplicateMatching,
+
+    /* Index de recherche, mis à jour par les méthodes modifiant la base de données (voir
+       Bot::_update_search_index) */
+    search_index: SearchIndex,
+
+    /* Traductions des messages, voir le module locale */
+    locale: Locale,
+
+    /* Stockage de la base de données. Si None à l’appel de Bot::setup, un YamlFileStorage sur
+       le chemin de fichier donné à Bot::setup est utilisé. */
+    storage: Option<StdMutex<StorageState>>,
+
+    /* Jeton d’arrêt du bot, voir Bot::shutdown */
+    shutdown: CancellationToken,
+
+    /* Tâche de mise à jour RSS, lancée dans Bot::setup et attendue par Bot::shutdown */
+    rss_task: Option<JoinHandle<()>>,
+
+    /* Intervalle et thread de sauvegarde périodique, voir Bot::autosave_interval */
+    autosave_interval: Option<Duration>,
+    autosave_task: Option<JoinHandle<()>>,
+
+    /* Date de la dernière exécution réussie de Object::maj_rss par le thread RSS */
+    last_rss_success: Option<DateTime<Utc>>,
+
+    /* Date et message de la dernière erreur de Object::maj_rss dans le thread RSS */
+    last_rss_error: Option<(DateTime<Utc>, String)>,
+
+    /* Gestionnaire des shards du client, récupéré dans Bot::setup pour fermer la connexion dans
+       Bot::shutdown. None si le bot n’est pas encore chargé. */
+    shard_manager: Option<Arc<ShardManager>>,
+
+    /* Délai d’expiration des interactions, voir Bot::interaction_timeout */
+    interaction_timeout: Duration,
+
+    /* Si true, un salon absolu impossible à charger est ignoré au démarrage au lieu de le faire
+       échouer. Voir Bot::skip_invalid_absolute_chans. */
+    skip_invalid_absolute_chans: bool,
+
+    /* Politique de démarrage. Si None, comportement historique : voir Bot::startup_policy. */
+    startup_policy: Option<StartupPolicy>,
+
+    /* Si true, les réponses des commandes de lecture sont éphémères. Voir
+       Bot::ephemeral_read_replies. */
+    ephemeral_read_replies: bool,
+
+    /* Nombre maximal de résultats affichés en entier par rechercher. Voir
+       Bot::inline_result_threshold. */
+    inline_result_threshold: usize,
+
+    /* Fuseau horaire des dates saisies et affichées. Voir Bot::timezone. */
+    timezone: tools::Timezone,
+
+    /* Activité affichée une fois le bot prêt. Voir Bot::activity. */
+    activity: ActivityData,
+
+    /* Activité dynamique et thread la rafraîchissant, voir Bot::dynamic_activity */
+    dynamic_activity: Option<Arc<DynamicActivity<T>>>,
+    activity_task: Option<JoinHandle<()>>,
+
+    /* Fenêtre de regroupement des mises à jour des affichans, signal des demandes de mise à jour
+       et thread les traitant, voir Bot::affichan_debounce */
+    affichan_debounce: Option<Duration>,
+    affichan_notify: Arc<Notify>,
+    affichan_task: Option<JoinHandle<()>>,
+
+    /* Canal de diffusion des changements de la base de données, voir Bot::subscribe */
+    changes: broadcast::Sender<ChangeEvent>,
+
+    /* Port du serveur de métriques. Si None, le serveur n’est pas démarré. */
+    #[cfg(feature = "metrics")]
+    metrics_port: Option<u16>,
+
+    /* Adresse d’écoute du serveur de métriques, locale par défaut */
+    #[cfg(feature = "metrics")]
+    metrics_address: IpAddr
+}
+
+/* Message à plusieurs pages envoyé par Bot::send_embed.
+   Migration : les pages et la position courante étaient auparavant dans deux tables indexées par
+   l’identifiant du multimessage (multimessages et mmpositions), perdues au redémarrage. Elles sont
+   désormais regroupées ici avec le message Discord portant la pagination, indexé par
+   Bot::mm_messages, et sauvegardées dans les métadonnées sous la clé « multimessages » (les pages
+   au format JSON). Une sauvegarde sans cette clé se charge sans multimessage, comme avant. */
+struct MultiMessage {
+    pages: Vec<CreateEmbed>,
+    position: usize,
+    /* Salon et message Discord portant la pagination, une fois le message envoyé */
+    message: Option<(ChannelId, MessageId)>,
+    /* Un message éphémère ne peut plus être modifié après un redémarrage : il n’est pas sauvegardé */
+    ephemeral: bool,
+    expiration: DateTime<Utc>
+}
+
+impl MultiMessage {
+    fn new(pages: Vec<CreateEmbed>, ephemeral: bool, timeout: Duration) -> Self {
+        Self {
+            pages,
+            position: 0,
+            message: None,
+            ephemeral,
+            expiration: Utc::now() + TimeDelta::from_std(timeout).unwrap_or(TimeDelta::MAX)
+        }
+    }
+
+    /* Forme sauvegardée du multimessage, None s’il ne peut pas être restauré après un redémarrage */
+    fn to_yaml(&self, id: &str) -> Option<Yaml> {
+        let (channel, message) = self.message.filter(|_| !self.ephemeral)?;
+        let pages = self.pages.iter()
+            .map(|page| serenity::json::to_string(page).ok().map(Yaml::String))
+            .collect::<Option<Vec<_>>>()?;
+        let mut yaml = yaml::Hash::new();
+        yaml.insert(Yaml::String("id".into()), Yaml::String(id.to_string()));
+        yaml.insert(Yaml::String("channel".into()), Yaml::Integer(channel.get() as i64));
+        yaml.insert(Yaml::String("message".into()), Yaml::Integer(message.get() as i64));
+        yaml.insert(Yaml::String("position".into()), Yaml::Integer(self.position as i64));
+        yaml.insert(Yaml::String("expiration".into()), Yaml::Integer(self.expiration.timestamp()));
+        yaml.insert(Yaml::String("pages".into()), Yaml::Array(pages));
+        Some(Yaml::Hash(yaml))
+    }
+
+    /* Relit un multimessage sauvegardé par to_yaml, None s’il est mal formé */
+    fn from_yaml(yaml: &Yaml) -> Option<(String, Self)> {
+        let pages = yaml["pages"].as_vec()?.iter()
+            .map(|page| serenity::json::from_str::<serenity::Embed>(page.as_str()?).ok().map(CreateEmbed::from))
+            .collect::<Option<Vec<_>>>()?;
+        let position = yaml["position"].as_i64()? as usize;
+        if position >= pages.len() {
+            return None;
+        }
+        Some((yaml["id"].as_str()?.to_string(), Self {
+            pages,
+            position,
+            message: Some((ChannelId::new(yaml["channel"].as_i64()? as u64), MessageId::new(yaml["message"].as_i64()? as u64))),
+            ephemeral: false,
+            expiration: DateTime::from_timestamp(yaml["expiration"].as_i64()?, 0)?
+        }))
+    }
+}
+
+/* Envoie la première page d’un multimessage déjà enregistré dans Bot::multimessages, puis grise
+   ses boutons et oublie ses pages après le délai d’expiration. Le verrou sur les données du bot
+   ne doit pas être tenu pendant le délai : il n’est pris que par la tâche d’expiration. Renvoie
+   le salon et le message portant la pagination, à enregistrer par Bot::_register_multimessage. */
+async fn _send_multimessage<T: Object>(ctx: &Context<'_, DataType<T>, ErrType>, id: String, first: CreateEmbed, timeout: Duration,
+                                      ephemeral: bool) -> Result<(ChannelId, MessageId), ErrType> {
+    let reply = ctx.send(CreateReply::default()
+        .embed(first)
+        .ephemeral(ephemeral)
+        .components(vec![CreateActionRow::Buttons(vec![
+            CreateButton::new(id.clone() + "-p")
+                .label("Précédent")
+                .disabled(true)
+                .style(ButtonStyle::Secondary),
+            CreateButton::new(id.clone() + "-n")
+                .label("Suivant")
+                .style(ButtonStyle::Secondary)
+        ])])).await?;
+    /* Expiration : les boutons sont grisés et les pages oubliées après le délai. Un message
+     * éphémère ne peut être modifié que par l’interaction qui l’a créé. */
+    let message = reply.into_message().await?;
+    let interaction = match ctx {
+        Context::Application(app_ctx) if ephemeral => Some(app_ctx.interaction.clone()),
+        _ => None
+    };
+    let cible = (message.channel_id, message.id);
+    _expire_multimessage(ctx.data().clone(), ctx.serenity_context().clone(), id, timeout, interaction, cible);
+    Ok(cible)
+}
+
+/* Lance la tâche grisant les boutons du multimessage et oubliant ses pages après le délai donné.
+   Un message éphémère ne peut être modifié que par l’interaction qui l’a créé. */
+fn _expire_multimessage<T: Object>(data: DataType<T>, serenity_ctx: SerenityContext, id: String, delai: Duration,
+                                   interaction: Option<CommandInteraction>, (channel, message): (ChannelId, MessageId)) {
+    tokio::spawn(async move {
+        time::sleep(delai).await;
+        data.lock().await._forget_multimessage(&id);
+        let resultat = match interaction {
+            Some(interaction) => interaction.edit_response(&serenity_ctx, EditInteractionResponse::new()
+                .components(vec![_multimessage_boutons_grises(&id)])).await.map(|_| ()),
+            None => channel.edit_message(&serenity_ctx, message, EditMessage::new()
+                .components(vec![_multimessage_boutons_grises(&id)])).await.map(|_| ())
+        };
+        if let Err(e) = resultat {
+            warn!("Impossible de griser les boutons du multimessage {id} : {e}");
+        }
+    });
+}
+
+/* Envoie un unique embed, sans boutons de navigation */
+async fn _send_single_embed<T: Object>(ctx: &Context<'_, DataType<T>, ErrType>, embeds: Vec<CreateEmbed>, ephemeral: bool) -> Result<(), ErrType> {
+    ctx.send(CreateReply::default().ephemeral(ephemeral).embed(embeds.into_iter().next()
+        .ok_or(ErrType::EmptyContainer("send_embed appelé avec aucun embed.".to_string()))?)).await?;
+    Ok(())
+}
+
+/* Boutons de navigation grisés d’un multimessage expiré ou dont les pages ont été perdues */
+fn _multimessage_boutons_grises(id: &str) -> CreateActionRow {
+    CreateActionRow::Buttons(vec![
+        CreateButton::new(format!("{id}-p"))
+            .label("Précédent")
+            .disabled(true)
+            .style(ButtonStyle::Secondary),
+        CreateButton::new(format!("{id}-n"))
+            .label("Suivant")
+            .disabled(true)
+            .style(ButtonStyle::Secondary)
+    ])
 }
 
 impl<T: Object> Default for Bot<T> {
@@ -150,38 +607,125 @@ impl<T: Object> Default for Bot<T> {
             last_rss_update: DateTime::from_timestamp(0, 0).unwrap(),
             self_id: None,
             history: VecDeque::new(),
+            history_max_objects: 10_000,
+            api_concurrency: 4,
+            debug_affichans: false,
+            event_handlers: Vec::new(),
+            permission_denied_message: None,
             multimessages: HashMap::new(),
-            mmpositions: HashMap::new(),
+            last_multimessage_id: 0,
+            mm_messages: HashMap::new(),
             affichans: Vec::new(),
-            data_file: String::new(),
+            unloaded_affichans: HashMap::new(),
             absolute_chans: HashMap::new(),
             update_affichans: false,
-            command_checker: Box::new(|_| async {Ok(true)}.boxed()),
+            command_checker: Arc::new(|_| async {Ok(true)}.boxed()),
+            permission_checker: Arc::new(|_, _| async {Ok(true)}.boxed()),
+            member_permissions: Box::new(|_| serenity::Permissions::empty()),
             owners: HashSet::new(),
-            log: None
+            log: None,
+            log_style: LogStyle::default(),
+            theme: Theme::default(),
+            normalize_options: NormalizeOptions::default(),
+            duplicate_matching: DuplicateMatching::default(),
+            search_index: SearchIndex::default(),
+            locale: Locale::new(),
+            storage: None,
+            shutdown: CancellationToken::new(),
+            rss_task: None,
+            autosave_interval: None,
+            autosave_task: None,
+            last_rss_success: None,
+            last_rss_error: None,
+            shard_manager: None,
+            interaction_timeout: Duration::from_secs(60),
+            skip_invalid_absolute_chans: false,
+            startup_policy: None,
+            ephemeral_read_replies: false,
+            inline_result_threshold: 3,
+            timezone: tools::Timezone::default(),
+            activity: ActivityData::playing("critiquer"),
+            dynamic_activity: None,
+            activity_task: None,
+            affichan_debounce: None,
+            affichan_notify: Arc::new(Notify::new()),
+            affichan_task: None,
+            changes: broadcast::channel(256).0,
+            #[cfg(feature = "metrics")]
+            metrics_port: None,
+            #[cfg(feature = "metrics")]
+            metrics_address: IpAddr::V4(Ipv4Addr::LOCALHOST)
         }
     }
 }
 
 impl<T: Object> Bot<T> {
 
-    /* Loads the database. One use in Bot::setup */
+    /* Applique Bot::member_permissions à une commande, si elle a un CommandData et que ses
+       permissions Discord n’ont pas été définies. Discord ne les prend en compte que pour les
+       commandes de premier niveau. */
+    fn _apply_member_permissions(&self, command: &mut poise::Command<DataType<T>, ErrType>) {
+        if !command.default_member_permissions.is_empty() {
+            return;
+        }
+        if let Some(data) = command.custom_data.downcast_ref::<CommandData>() {
+            command.default_member_permissions = (self.member_permissions)(data.permission);
+        }
+    }
+
+    /* Charge un salon absolu. L’erreur renvoyée indique le nom et l’identifiant du salon. */
+    async fn _load_absolute_chan(ctx: &SerenityContext, name: &str, chan_id: ChannelId) -> Result<GuildChannel, ErrType> {
+        match chan_id.to_channel(ctx).await {
+            Ok(chan) => chan.guild().ok_or(ErrType::ObjectNotFound(
+                format!("Le salon absolu {name} (id: {chan_id}) n’est pas un salon de serveur."))),
+            Err(e) => Err(ErrType::ObjectNotFound(
+                format!("Impossible de charger le salon absolu {name} (id: {chan_id}) : {e}.")))
+        }
+    }
+
+    /* Loads the database. Used in Bot::setup and Bot::_load_backup */
     fn _load_database(data: &Yaml) -> Result<HashMap<u64, T>, ErrType> {
-        println!("Chargement des données.");
+        info!("Chargement des données.");
 
-        Ok(data["entries"].as_vec()
+        data["entries"].as_vec()
             .ok_or(ErrType::YamlParseError("Dans les données, entries n’est pas un tableau.".to_string()))?
             .iter().map(|entry| match T::from_yaml(entry) {
-            Ok(obj) => (obj.get_id(), obj),
+            Ok(obj) => Ok((obj.get_id(), obj)),
             Err(e) => {
                 let mut debug_out = String::new();
                 let mut debug_emitter = YamlEmitter::new(&mut debug_out);
                 debug_emitter.compact(false);
                 debug_emitter.multiline_strings(true);
                 let _ = debug_emitter.dump(entry);
-                panic!("Erreur de chargement ({e}) dans le yaml suivant: {debug_out}")
+                Err(ErrType::YamlParseError(format!("Erreur de chargement ({e}) dans le yaml suivant: {debug_out}")))
             }
-        }).collect())
+        }).collect()
+    }
+
+    /* Sauvegarde de secours du stockage, s’il en conserve une (voir Storage::backup). Utilisé
+       dans la commande restaurer_backup. */
+    pub(crate) fn _backup_storage(&self) -> Option<Box<dyn Storage>> {
+        self.storage.as_ref()?.lock().unwrap_or_else(|e| e.into_inner()).backend.backup()
+    }
+
+    /* Charge la base de données et la date de dernière mise à jour RSS depuis la sauvegarde de
+       secours donnée. Utilisé dans la commande restaurer_backup. */
+    pub(crate) fn _load_backup(
+        backup: &dyn Storage
+    ) -> Result<(HashMap<u64, T>, DateTime<Utc>), ErrType> {
+        let data = Self::_load_from_storage(backup)?
+            .ok_or(ErrType::ObjectNotFound("Sauvegarde de secours absente.".to_string()))?;
+        let data = &data[0];
+        let last_update = DateTime::from_timestamp(data["last_rss_update"].as_i64().unwrap_or(0), 0)
+            .ok_or(ErrType::YamlParseError("Mauvais format de date pour last_rss_update.".to_string()))?;
+        Ok((Self::_load_database(data)?, last_update))
+    }
+
+    /* Chemin du fichier contenant la sauvegarde, s’il y en a un (voir Storage::file_path).
+       Utilisé dans les commandes bdd et stats. */
+    pub(crate) fn _storage_file(&self) -> Option<String> {
+        self.storage.as_ref()?.lock().unwrap_or_else(|e| e.into_inner())
+            .backend.file_path().map(str::to_string)
     }
 
     /// Créé un bot avec les valeurs par défaut, puis appelle appelle automatiquement [`Bot::setup`].
@@ -213,11 +757,15 @@ impl<T: Object> Bot<T> {
     /// commande de publier des messages dans ces salons, indépendemment du salon dans lequel
     /// elles ont été lancées. Ils sont accessibles par [`Bot::get_absolute_chan`].
     ///
+    /// Le comportement en cas de salon d’affichage, de salon absolu ou de salon des logs
+    /// impossible à charger est défini par [`Bot::startup_policy`].
+    ///
     /// # Panics
     /// Cette méthode essaye au maximum de renvoyer ses erreurs, mais panique en cas d’erreur
     /// dans le chargement du fichier de sauvegarde en YAML pour éviter toute corruption ou
     /// suppression accidentelle de données.
     ///
+    #[instrument(name = "setup", skip_all)]
     pub async fn setup(mut self,
         token: String,
         intents: GatewayIntents,
@@ -226,32 +774,65 @@ impl<T: Object> Bot<T> {
         affichans: Vec<Affichan<T>>,
         absolute_chans: HashMap<&'static str, u64>
     ) -> Result<Client, ErrType> {
-        println!("Lancement du bot.");
-        let data_str = fs::read_to_string(savefile_path);
-        let data = data_str.map_or(None, |s| YamlLoader::load_from_str(s.as_str()).ok());
+        info!("Lancement du bot.");
+        let storage = self.storage.get_or_insert_with(||
+            StdMutex::new(StorageState::new(Box::new(YamlFileStorage::new(savefile_path)))));
+        let data = Self::_load_from_storage(&*storage.get_mut().unwrap_or_else(|e| e.into_inner()).backend)
+            .unwrap_or_else(|e| panic!("{e}"));
         let mut last_update = 0;
 
         self.database = {
             if let Some(data) = &data {
                 let data = &data[0];
                 last_update = data["last_rss_update"].as_i64().unwrap_or(0);
-                Self::_load_database(data)?
+                self.history = Self::_load_history(data);
+                /* Multimessages encore valides lors de la dernière sauvegarde */
+                for (id, multimessage) in data["multimessages"].as_vec().into_iter().flatten().filter_map(MultiMessage::from_yaml) {
+                    if multimessage.expiration > Utc::now() {
+                        if let Some((_, message)) = multimessage.message {
+                            self.mm_messages.insert(message, id.clone());
+                        }
+                        self.multimessages.insert(id, multimessage);
+                    }
+                }
+                Self::_load_database(data).unwrap_or_else(|e| panic!("{e}"))
             } else {
-                println!("Pas de base de donnée trouvée : création d’une nouvelle.");
+                info!("Pas de base de donnée trouvée : création d’une nouvelle.");
                 HashMap::new()
             }
         };
+        self.rebuild_search_index();
+
+        if let Some(storage) = &self.storage {
+            storage.lock().unwrap_or_else(|e| e.into_inner())
+                .mark_written(self.database.iter().map(|(&id, object)| (id, object.serialize())))?;
+        }
 
         self.last_rss_update = DateTime::from_timestamp(last_update, 0)
             .ok_or(ErrType::YamlParseError("Mauvais format de date pour last_rss_update.".to_string()))?;
 
         self.affichans = affichans;
+        for affichan in self.affichans.iter_mut() {
+            affichan.set_api_concurrency(self.api_concurrency);
+            affichan.set_debug(self.debug_affichans);
+        }
+
+        #[cfg(feature = "metrics")]
+        {
+            metrics::record_affichans(self.affichans.len());
+            if let Some(port) = self.metrics_port {
+                metrics::serve(SocketAddr::new(self.metrics_address, port)).await?;
+            }
+        }
 
-        self.data_file = savefile_path.to_string();
+        self._check_button_ids();
 
-        println!("Création du framework.");
+        info!("Création du framework.");
 
         commands.append(&mut commands::command_list());
+        for command in commands.iter_mut() {
+            self._apply_member_permissions(command);
+        }
 
         let framework = Framework::builder()
             .options(poise::FrameworkOptions {
@@ -259,22 +840,53 @@ impl<T: Object> Bot<T> {
                 /* ------ event handler ----- */
                 event_handler: |ctx, event, _framework_context, data| {
                     Box::pin(async move {
+                        /* Traitements de l’utilisateur, sans tenir le verrou */
+                        let event_handlers = data.lock().await.event_handlers.clone();
+                        for event_handler in event_handlers {
+                            match event_handler(ctx, event, data).await {
+                                Ok(true) => {},
+                                Ok(false) => return Ok(()),
+                                Err(e) => {
+                                    error!("Erreur lors du traitement d’un évènement : {e}");
+                                    return Err(e);
+                                }
+                            }
+                        }
+
+                        /* Objet visé par une réaction, cherché sans tenir le verrou s’il faut
+                           récupérer le message auprès de Discord */
+                        let reaction_object = match event {
+                            FullEvent::ReactionAdd {add_reaction} => match Self::_reaction_object(ctx, add_reaction, data).await {
+                                Ok(object_id) => object_id,
+                                Err(e) => {
+                                    error!("Erreur lors de la réception d’un évènement : {e}");
+                                    return Err(e);
+                                }
+                            },
+                            _ => None
+                        };
+
                         let bot = &mut data.lock().await;
 
                         /* Traitement des évènements */
                         if let Err(e) = match event {
                             FullEvent::InteractionCreate {interaction: Interaction::Component(component), ..} => bot.handle_interaction(ctx, &mut component.clone()).await,
+                            FullEvent::InteractionCreate {interaction: Interaction::Modal(modal), ..} => bot.handle_modal(ctx, modal).await,
                             FullEvent::MessageDelete {deleted_message_id, ..} => bot.check_deletions(ctx, &deleted_message_id).await,
+                            FullEvent::MessageUpdate {event, ..} => bot.check_edits(ctx, event).await,
+                            FullEvent::ReactionAdd {add_reaction} => bot.handle_reaction(ctx, add_reaction, reaction_object).await,
                             _ => return Ok(()) /* Évite de mettre à jour les affichans ou sauvegarde à chaque event */
                         } {
-                            eprintln!("Erreur lors de la réception d’un évènement : {e}");
+                            error!("Erreur lors de la réception d’un évènement : {e}");
                             return Err(e);
                         }
 
-                        /* Mise à jour des affichans */
-                        if bot.update_affichans {
+                        /* Mise à jour des affichans, différée si elle est groupée */
+                        if bot.update_affichans && bot.affichan_debounce.is_some() {
+                            bot.affichan_notify.notify_one();
+                        } else if bot.update_affichans {
                             if let Err(e) = bot.update_affichans(ctx).await {
-                                eprintln!("Erreur lors de la mise à jour des affichans : {e}");
+                                error!("Erreur lors de la mise à jour des affichans : {e}");
                                 return Err(e);
                             }
                             bot.update_affichans = false;
@@ -282,7 +894,7 @@ impl<T: Object> Bot<T> {
 
                         /* Sauvegarde à chaque évènement reçu */
                         if let Err(e) = bot.save() {
-                            eprintln!("Erreur lors d’une sauvegarde de routine: {e}");
+                            error!("Erreur lors d’une sauvegarde de routine: {e}");
                         }
                         Ok(())
 
@@ -294,18 +906,19 @@ impl<T: Object> Bot<T> {
             /* ----- setup ------ */
             .setup(|ctx, ready, framework| {
                 Box::pin(async move {
-                    println!("Bot connecté à Discord. Réglage des derniers détails.");
+                    info!("Bot connecté à Discord. Réglage des derniers détails.");
                     ctx.idle();
-                    println!("Enregistrement des commandes.");
+                    info!("Enregistrement des commandes.");
                     poise::builtins::register_globally(ctx, &framework.options().commands).await?;
-                    println!("Récupération de l’identifiant.");
+                    self.shard_manager = Some(framework.shard_manager().clone());
+                    info!("Récupération de l’identifiant.");
                     self.self_id = Some(ready.user.id);
-                    println!("Chargement des salons d’affichage.");
+                    info!("Chargement des salons d’affichage.");
                     ctx.set_activity(Some(ActivityData::custom("Chargement des salons…")));
                     let affichans_data = if let Some(data) = &data {
                         Some(&data[0]["affichans"])
                     } else {None};
-                    try_join_all(self.affichans.iter_mut().map(
+                    let resultats = join_all(self.affichans.iter_mut().map(
                         |affichan| {
                             let affichan_data = affichans_data
                                 .and_then( |affichans_data| affichans_data.as_hash()
@@ -313,63 +926,361 @@ impl<T: Object> Bot<T> {
                             );
                             affichan.init(&self.database, self.self_id.as_ref().unwrap(), affichan_data, ctx)
                         }
-                    )).await?;
-                    println!("Chargement des salons absolus.");
-
-                    self.absolute_chans = try_join_all(absolute_chans.iter().map(|(&name, chan_id)| {
-                        async move {
-                            match ChannelId::new(*chan_id).to_channel(ctx).await {
-                                Ok(chan) => Ok((name, chan.guild().unwrap())),
-                                Err(e) => Err(e)
+                    )).await;
+                    let mut echecs = HashSet::new();
+                    for (chan_id, resultat) in self.affichans.iter().map(Affichan::get_chan_id).zip(resultats) {
+                        match resultat {
+                            Ok(()) => {},
+                            Err(e) if !self._startup_error_is_fatal(true) => {
+                                error!("Chargement du salon d’affichage {chan_id} impossible : {e} Démarrage sans ce salon.");
+                                echecs.insert(chan_id);
+                                if let Some(affichan_data) = affichans_data.and_then(|affichans_data| affichans_data.as_hash())
+                                    .and_then(|affichans_data| affichans_data.get(&Yaml::Integer(chan_id as i64))) {
+                                    self.unloaded_affichans.insert(chan_id, affichan_data.clone());
+                                }
+                            },
+                            Err(e) => {
+                                error!("Chargement du salon d’affichage {chan_id} impossible : {e} Arrêt du bot.");
+                                framework.shard_manager().shutdown_all().await;
+                                return Err(e);
+                            }
+                        }
+                    }
+                    self.affichans.retain(|affichan| !echecs.contains(&affichan.get_chan_id()));
+                    info!("Chargement des salons absolus.");
+
+                    for (name, chan) in join_all(absolute_chans.iter().map(|(&name, &chan_id)| async move {
+                        (name, Self::_load_absolute_chan(ctx, name, ChannelId::new(chan_id)).await)
+                    })).await {
+                        match chan {
+                            Ok(chan) => {
+                                self.absolute_chans.insert(name.to_string(), PreloadedChannel::Loaded(chan));
+                            },
+                            Err(e) if !self._startup_error_is_fatal(!self.skip_invalid_absolute_chans) => {
+                                error!("{e} Démarrage sans ce salon, qui sera rechargé à sa prochaine utilisation.");
+                                self.absolute_chans.insert(name.to_string(),
+                                    PreloadedChannel::Unloaded(ChannelId::new(absolute_chans[name])));
+                            },
+                            Err(e) => {
+                                error!("{e} Arrêt du bot.");
+                                framework.shard_manager().shutdown_all().await;
+                                return Err(e);
                             }
                         }
-                    })).await.unwrap().into_iter().collect();
-
-                    println!("Chargement du salon des logs, s'il existe.");
-                    if let Some(log) = self.log {
-                        self.log = match log.load(ctx).await.ok() {
-                            Some(chan) => Some(PreloadedChannel::Loaded(chan)),
-                            None => {
-                                eprintln!("Erreur de chargement du salon des logs.");
+                    }
+
+                    info!("Chargement du salon des logs, s'il existe.");
+                    if let Some(log) = self.log.take() {
+                        self.log = match log.load(ctx).await {
+                            Ok(chan) => Some(PreloadedChannel::Loaded(chan)),
+                            Err(e) if !self._startup_error_is_fatal(false) => {
+                                error!("Erreur de chargement du salon des logs : {e}");
                                 None
+                            },
+                            Err(e) => {
+                                error!("Erreur de chargement du salon des logs : {e} Arrêt du bot.");
+                                framework.shard_manager().shutdown_all().await;
+                                return Err(e);
                             }
                         };
                     }
 
+                    let shutdown = self.shutdown.clone();
+                    let shutdown_autosave = self.shutdown.clone();
+                    let autosave_interval = self.autosave_interval;
+                    let dynamic_activity = self.dynamic_activity.clone();
+                    let affichan_debounce = self.affichan_debounce;
+                    let affichan_notify = self.affichan_notify.clone();
+                    let shutdown_affichans = self.shutdown.clone();
+                    let multimessages_restaures: Vec<(String, Duration, (ChannelId, MessageId))> = self.multimessages.iter()
+                        .filter_map(|(id, multimessage)| Some((id.clone(),
+                            (multimessage.expiration - Utc::now()).to_std().unwrap_or_default(), multimessage.message?)))
+                        .collect();
                     let bot_mutex = Arc::new(Mutex::new(self));
                     let bot_mutex_2 = bot_mutex.clone();
-                    println!("Démarrage du thread RSS.");
-                    tokio::spawn(async move {
+                    for (id, delai, message) in multimessages_restaures {
+                        _expire_multimessage(bot_mutex_2.clone(), ctx.clone(), id, delai, None, message);
+                    }
+                    info!("Démarrage du thread RSS.");
+                    let rss_task = tokio::spawn(async move {
                         let mut delay = time::interval(Duration::from_secs(600));
-                        loop {
-                            if let Err(e) = T::maj_rss(&bot_mutex).await {
-                                println!("Erreur lors d’une mise à jour RSS: {e}");
+                        while !shutdown.is_cancelled() {
+                            let resultat = T::maj_rss(&bot_mutex).instrument(info_span!("maj_rss")).await;
+                            #[cfg(feature = "metrics")]
+                            metrics::record_rss_update(resultat.is_ok());
+                            {
+                                let mut bot = bot_mutex.lock().await;
+                                match resultat {
+                                    Ok(()) => bot.last_rss_success = Some(Utc::now()),
+                                    Err(e) => {
+                                        error!("Erreur lors d’une mise à jour RSS: {e}");
+                                        bot.last_rss_error = Some((Utc::now(), e.to_string()));
+                                    }
+                                }
+                            }
+                            tokio::select! {
+                                _ = shutdown.cancelled() => {},
+                                _ = delay.tick() => {}
                             }
-                            delay.tick().await;
                         }
+                        info!("Arrêt du thread RSS.");
+                    });
+                    let autosave_task = autosave_interval.map(|intervalle| {
+                        info!("Démarrage du thread de sauvegarde périodique.");
+                        let bot_mutex = bot_mutex_2.clone();
+                        let shutdown = shutdown_autosave;
+                        tokio::spawn(async move {
+                            let mut delay = time::interval(intervalle.max(Duration::from_secs(1)));
+                            /* Le premier tick est immédiat */
+                            delay.tick().await;
+                            loop {
+                                tokio::select! {
+                                    _ = shutdown.cancelled() => break,
+                                    _ = delay.tick() => {}
+                                }
+                                if let Err(e) = bot_mutex.lock().await.save() {
+                                    error!("Erreur lors d’une sauvegarde périodique : {e}");
+                                }
+                            }
+                            info!("Arrêt du thread de sauvegarde périodique.");
+                        })
                     });
-                    println!("Chargement terminé !");
-                    ctx.set_activity(Some(ActivityData::playing("critiquer")));
+                    let activity = {
+                        let mut bot = bot_mutex_2.lock().await;
+                        bot.rss_task = Some(rss_task);
+                        bot.autosave_task = autosave_task;
+                        bot.activity.clone()
+                    };
+                    info!("Chargement terminé !");
+                    ctx.set_activity(Some(activity));
+                    if let Some(dynamic_activity) = dynamic_activity {
+                        info!("Démarrage du thread d’activité dynamique.");
+                        let bot_mutex = bot_mutex_2.clone();
+                        let shutdown = bot_mutex.lock().await.shutdown.clone();
+                        let serenity_ctx = ctx.clone();
+                        let activity_task = tokio::spawn(async move {
+                            let mut delay = time::interval(Duration::from_secs(300));
+                            loop {
+                                tokio::select! {
+                                    _ = shutdown.cancelled() => break,
+                                    _ = delay.tick() => {}
+                                }
+                                let texte = dynamic_activity(&*bot_mutex.lock().await);
+                                serenity_ctx.set_activity(Some(ActivityData::custom(texte)));
+                            }
+                            info!("Arrêt du thread d’activité dynamique.");
+                        });
+                        bot_mutex_2.lock().await.activity_task = Some(activity_task);
+                    }
+                    if let Some(fenetre) = affichan_debounce {
+                        info!("Démarrage du thread de mise à jour groupée des salons d’affichage.");
+                        let bot_mutex = bot_mutex_2.clone();
+                        let shutdown = shutdown_affichans;
+                        let serenity_ctx = ctx.clone();
+                        let affichan_task = tokio::spawn(async move {
+                            loop {
+                                tokio::select! {
+                                    _ = shutdown.cancelled() => break,
+                                    _ = affichan_notify.notified() => {}
+                                }
+                                /* Les demandes arrivant pendant la fenêtre sont regroupées */
+                                tokio::select! {
+                                    _ = shutdown.cancelled() => break,
+                                    _ = time::sleep(fenetre) => {}
+                                }
+                                let mut bot = bot_mutex.lock().await;
+                                if bot.update_affichans {
+                                    match bot.update_affichans(&serenity_ctx).await {
+                                        Ok(()) => bot.update_affichans = false,
+                                        Err(e) => error!("Erreur lors de la mise à jour groupée des affichans : {e}")
+                                    }
+                                }
+                            }
+                            info!("Arrêt du thread de mise à jour groupée des salons d’affichage.");
+                        });
+                        bot_mutex_2.lock().await.affichan_task = Some(affichan_task);
+                    }
                     ctx.online();
                     Ok(bot_mutex_2)
                 })
             }).build();
 
-        println!("Création du bot terminé, client crée.");
+        info!("Création du bot terminé, client crée.");
 
         Ok(ClientBuilder::new(token, intents).framework(framework).await?)
     }
 
-    /// Renvoie une référence vers le salon du nom donné, ou une erreur s’il n’existe pas.
-    pub fn get_absolute_chan(&self, name: &'static str) -> Result<&GuildChannel, ErrType> {
-        self.absolute_chans.get(name).ok_or(ErrType::ObjectNotFound(format!("Salon absolu {name} inexistant.")))
+    /// Arrête proprement le bot : annule le thread RSS, les threads de sauvegarde périodique,
+    /// d’activité dynamique et de mise à jour groupée des salons d’affichage, attend la fin de la
+    /// mise à jour RSS éventuellement en cours, sauvegarde une dernière fois la base de données,
+    /// puis ferme la connexion à Discord, ce qui termine [`Client::start`].
+    ///
+    /// La connexion est fermée même si la sauvegarde échoue ; l’erreur de sauvegarde est alors
+    /// renvoyée. Le verrou sur le bot ne doit pas être tenu lors de l’appel.
+    pub async fn shutdown(bot: &DataType<T>) -> Result<(), ErrType> {
+        info!("Arrêt du bot.");
+        let (rss_task, autosave_task, activity_task, affichan_task) = {
+            let mut bot = bot.lock().await;
+            bot.shutdown.cancel();
+            (bot.rss_task.take(), bot.autosave_task.take(), bot.activity_task.take(), bot.affichan_task.take())
+        };
+        if let Some(rss_task) = rss_task {
+            if let Err(e) = rss_task.await {
+                error!("Erreur lors de l’arrêt du thread RSS : {e}");
+            }
+        }
+        if let Some(autosave_task) = autosave_task {
+            if let Err(e) = autosave_task.await {
+                error!("Erreur lors de l’arrêt du thread de sauvegarde périodique : {e}");
+            }
+        }
+        if let Some(activity_task) = activity_task {
+            if let Err(e) = activity_task.await {
+                error!("Erreur lors de l’arrêt du thread d’activité dynamique : {e}");
+            }
+        }
+        if let Some(affichan_task) = affichan_task {
+            if let Err(e) = affichan_task.await {
+                error!("Erreur lors de l’arrêt du thread de mise à jour groupée des salons d’affichage : {e}");
+            }
+        }
+        let (resultat, shard_manager) = {
+            let bot = bot.lock().await;
+            (bot.save(), bot.shard_manager.clone())
+        };
+        if let Err(e) = &resultat {
+            error!("Erreur lors de la dernière sauvegarde : {e}");
+        }
+        if let Some(shard_manager) = shard_manager {
+            shard_manager.shutdown_all().await;
+        }
+        resultat
+    }
+
+    /// Renvoie le jeton d’arrêt du bot, annulé par [`Bot::shutdown`]. Il permet d’arrêter
+    /// proprement les tâches lancées par le bot utilisant la bibliothèque.
+    pub fn shutdown_token(&self) -> CancellationToken {
+        self.shutdown.clone()
+    }
+
+    /// Renvoie une référence vers le salon absolu du nom donné.
+    ///
+    /// Renvoie [`ErrType::ObjectNotFound`] si aucun salon absolu de ce nom n’a été défini, et
+    /// [`ErrType::UnloadedItem`] (avec l’identifiant du salon) si le salon est défini mais n’a pas
+    /// pu être chargé, par exemple à cause d’une indisponibilité de Discord au démarrage. Dans ce
+    /// second cas, [`Bot::load_absolute_chan`] tente de le recharger.
+    pub fn get_absolute_chan(&self, name: &str) -> Result<&GuildChannel, ErrType> {
+        self.absolute_chans.get(name)
+            .ok_or(ErrType::ObjectNotFound(format!("Salon absolu {name} inexistant.")))?
+            .get()
+    }
+
+    /// Équivalent de [`Bot::get_absolute_chan`] qui charge le salon s’il ne l’est pas encore. Un
+    /// salon absolu qui n’a pas pu être chargé au démarrage (voir
+    /// [`Bot::skip_invalid_absolute_chans`]) est ainsi disponible dès que Discord le permet.
+    ///
+    /// Renvoie [`ErrType::ObjectNotFound`] si aucun salon absolu de ce nom n’a été défini, et
+    /// [`ErrType::UnloadedItem`] si le chargement échoue encore ; la cause de l’échec est loggée.
+    pub async fn load_absolute_chan(&mut self, name: &str, ctx: &SerenityContext) -> Result<&GuildChannel, ErrType> {
+        let chan = self.absolute_chans.get_mut(name)
+            .ok_or(ErrType::ObjectNotFound(format!("Salon absolu {name} inexistant.")))?;
+        if let PreloadedChannel::Unloaded(chan_id) = chan {
+            let chan_id = *chan_id;
+            match Self::_load_absolute_chan(ctx, name, chan_id).await {
+                Ok(loaded) => {
+                    info!("Salon absolu {name} rechargé.");
+                    *chan = PreloadedChannel::Loaded(loaded);
+                },
+                Err(e) => {
+                    warn!("{e}");
+                    return Err(ErrType::UnloadedItem(chan_id.get()));
+                }
+            }
+        }
+        chan.get()
+    }
+
+    /// Charge le salon d’identifiant donné et l’enregistre comme salon absolu sous le nom donné,
+    /// en remplaçant le salon précédent de ce nom s’il existe. Permet d’ajouter ou de modifier un
+    /// salon absolu sans redémarrer le bot.
+    ///
+    /// La modification n’est pas sauvegardée : au prochain démarrage, les salons absolus sont
+    /// ceux donnés à [`Bot::setup`]. Renvoie une erreur si le salon n’existe pas ou n’est pas
+    /// un salon de serveur, auquel cas les salons absolus ne sont pas modifiés.
+    pub async fn set_absolute_chan(&mut self, name: impl Into<String>, chan_id: ChannelId, ctx: &SerenityContext) -> Result<(), ErrType> {
+        let name = name.into();
+        let chan = Self::_load_absolute_chan(ctx, &name, chan_id).await?;
+        info!("Salon absolu {name} défini sur {} (id: {chan_id}).", chan.name);
+        self.absolute_chans.insert(name, PreloadedChannel::Loaded(chan));
+        Ok(())
+    }
+
+    /// Change le salon des logs (voir [`Bot::set_log`]) sans redémarrer le bot.
+    ///
+    /// Comme pour [`Bot::set_absolute_chan`], la modification n’est pas sauvegardée. Renvoie une
+    /// erreur si le salon n’existe pas ou n’est pas un salon de serveur, auquel cas le salon des
+    /// logs n’est pas modifié.
+    pub async fn set_log_chan(&mut self, chan_id: ChannelId, ctx: &SerenityContext) -> Result<(), ErrType> {
+        let chan = PreloadedChannel::Unloaded(chan_id).load(ctx).await?;
+        info!("Salon des logs défini sur {} (id: {chan_id}).", chan.name);
+        self.log = Some(PreloadedChannel::Loaded(chan));
+        Ok(())
+    }
+
+    /// Désactive le salon des logs sans redémarrer le bot : [`Bot::log`] ne produit plus aucun
+    /// message jusqu’au prochain appel à [`Bot::set_log_chan`] ou au redémarrage.
+    pub fn unset_log(&mut self) {
+        info!("Salon des logs désactivé.");
+        self.log = None;
     }
 
     /// Permet de définir une fonction pour `command_checker` autre que celle par défaut.
     ///
     /// La valeur par défaut de cette fonction renvoie toujours `true`.
     pub fn command_checker(mut self, f: Box<CommandChecker<T>>) -> Self {
-        self.command_checker = f;
+        self.command_checker = Arc::from(f);
+        self
+    }
+
+    /// Permet de définir une fonction vérifiant, sans effet de bord (pas de message envoyé),
+    /// si l’auteur d’une commande a le niveau de [`command_data::Permission`] donné. Elle est utilisée par la
+    /// commande `/aide` pour n’afficher que les commandes accessibles à l’utilisateur, et devrait
+    /// donc être cohérente avec la fonction de [`Bot::command_checker`].
+    ///
+    /// La valeur par défaut de cette fonction renvoie toujours `true`, comme celle de
+    /// [`Bot::command_checker`] : toutes les commandes sont alors affichées.
+    pub fn permission_checker(mut self, f: Box<PermissionChecker<T>>) -> Self {
+        self.permission_checker = Arc::from(f);
+        self
+    }
+
+    /// Permet de définir les permissions Discord (`default_member_permissions`) requises pour voir
+    /// les commandes selon leur niveau de [`command_data::Permission`]. Discord masque alors
+    /// nativement les commandes aux membres n’ayant pas ces permissions, en plus de la vérification
+    /// faite par [`Bot::command_checker`].
+    ///
+    /// Les permissions sont appliquées lors de l’enregistrement des commandes, aux seules commandes
+    /// ayant un [`CommandData`] et dont `default_member_permissions` n’a pas déjà été défini. Par
+    /// défaut, aucune permission n’est requise : toutes les commandes sont visibles par tous.
+    ///
+    /// ```ignore
+    /// bot.member_permissions(Box::new(|permission| match permission {
+    ///     Permission::MANAGE => Permissions::MANAGE_MESSAGES,
+    ///     _ => Permissions::empty()
+    /// }))
+    /// ```
+    pub fn member_permissions(mut self, f: Box<MemberPermissions>) -> Self {
+        self.member_permissions = f;
+        self
+    }
+
+    /// Définit le message éphémère envoyé par [`CommandData::check`] lorsque la fonction de
+    /// [`Bot::command_checker`] refuse l’exécution d’une commande sans avoir elle-même répondu.
+    ///
+    /// Par défaut, le message est celui de la clé `permission.refusee` de la [`Locale`] (voir
+    /// [`Bot::tr`]).
+    pub fn permission_denied_message(mut self, message: String) -> Self {
+        self.permission_denied_message = Some(message);
         self
     }
 
@@ -385,84 +1296,557 @@ impl<T: Object> Bot<T> {
         self
     }
 
+    /// Définit les options de normalisation des chaînes de caractères utilisées par
+    /// [`Bot::search`] (voir [`tools::basicize_with`]).
+    ///
+    /// Par défaut, la normalisation est celle de [`tools::basicize`].
+    pub fn normalize_options(mut self, options: NormalizeOptions) -> Self {
+        self.normalize_options = options;
+        self.rebuild_search_index();
+        self
+    }
+
+    /// Définit le mode de comparaison des noms utilisé par [`Bot::find_duplicates`] (voir
+    /// [`DuplicateMatching`]).
+    ///
+    /// Par défaut, les noms sont comparés après normalisation.
+    pub fn duplicate_matching(mut self, matching: DuplicateMatching) -> Self {
+        self.duplicate_matching = matching;
+        self
+    }
+
+    /// Définit le port du serveur HTTP exposant les métriques du bot au format Prometheus
+    /// (voir [`metrics`]). Sans appel à cette méthode, le serveur n’est pas démarré.
+    ///
+    /// Le serveur n’écoute que sur l’adresse locale (`127.0.0.1`) par défaut ; voir
+    /// [`Bot::metrics_address`] pour l’exposer sur le réseau.
+    #[cfg(feature = "metrics")]
+    pub fn metrics_port(mut self, port: u16) -> Self {
+        self.metrics_port = Some(port);
+        self
+    }
+
+    /// Définit l’adresse d’écoute du serveur de métriques (voir [`Bot::metrics_port`]). Par
+    /// défaut, `127.0.0.1` : les métriques ne sont accessibles que depuis la machine du bot.
+    /// Utiliser par exemple `0.0.0.0` pour les rendre accessibles depuis le réseau.
+    #[cfg(feature = "metrics")]
+    pub fn metrics_address(mut self, address: IpAddr) -> Self {
+        self.metrics_address = address;
+        self
+    }
+
+    /// Définit les traductions des messages envoyés aux utilisateurs par la bibliothèque
+    /// (voir [`locale`]). Les messages absents de la table restent en français.
+    pub fn locale(mut self, locale: Locale) -> Self {
+        self.locale = locale;
+        self
+    }
+
+    /// Renvoie le message associé à la clé donnée (voir [`locale`]), traduit d’après
+    /// [`Bot::locale`] ou en français par défaut, en y remplaçant les paramètres donnés.
+    ///
+    /// Si la clé n’existe pas, la clé elle-même est renvoyée.
+    pub fn tr(&self, key: &str, args: &[(&str, &str)]) -> String {
+        let message = self.locale.get(key).map(String::as_str)
+            .or_else(|| locale::default_message(key))
+            .unwrap_or(key);
+        locale::format_message(message, args)
+    }
+
+    /// Définit un moyen de stockage de la base de données (voir [`storage`]) remplaçant la
+    /// sauvegarde par défaut dans un fichier YAML ([`YamlFileStorage`]). Le chemin de fichier donné
+    /// à [`Bot::setup`] n’est alors plus utilisé : les commandes `/bdd`, `/stats` et
+    /// `/restaurer_backup` passent par le stockage (voir [`Storage::file_path`] et
+    /// [`Storage::backup`]).
+    pub fn storage(mut self, storage: impl Storage + 'static) -> Self {
+        self.storage = Some(StdMutex::new(StorageState::new(Box::new(storage))));
+        self
+    }
+
+    /// Définit la fonction de traitement des évènements Discord, en remplaçant celles ajoutées
+    /// précédemment (voir [`Bot::add_event_handler`]).
+    pub fn event_handler(mut self, f: Box<EventHandler<T>>) -> Self {
+        self.event_handlers = vec![Arc::from(f)];
+        self
+    }
+
+    /// Ajoute une fonction de traitement des évènements Discord, appelée pour chaque évènement
+    /// reçu avant son traitement par la bibliothèque (boutons, suppressions et modifications de
+    /// messages, réactions).
+    ///
+    /// Les fonctions sont appelées dans l’ordre de leur ajout. Si l’une d’elles renvoie `false`
+    /// ou une erreur, les suivantes ne sont pas appelées et l’évènement n’est pas traité par la
+    /// bibliothèque : il n’est traité que si toutes renvoient `true`.
+    ///
+    /// ```ignore
+    /// bot.add_event_handler(Box::new(|_ctx, event, _data| async move {
+    ///     info!("Évènement reçu : {}", event.snake_case_name());
+    ///     Ok(true)
+    /// }.boxed()))
+    /// ```
+    pub fn add_event_handler(mut self, f: Box<EventHandler<T>>) -> Self {
+        self.event_handlers.push(Arc::from(f));
+        self
+    }
+
+    /// Définit le nombre maximal d’appels simultanés à l’API Discord effectués par chaque salon
+    /// d’affichage lors de son chargement et de ses mises à jour (publication, modification et
+    /// suppression de messages). Par défaut, 4 appels simultanés au plus.
+    ///
+    /// Une valeur plus élevée accélère les mises à jour importantes mais expose davantage aux
+    /// limites de débit de Discord. Une valeur de 0 est traitée comme 1.
+    pub fn api_concurrency(mut self, api_concurrency: usize) -> Self {
+        self.api_concurrency = api_concurrency;
+        self
+    }
+
+    /// Active la sauvegarde périodique de la base de données (voir [`Bot::save`]) à l’intervalle
+    /// donné, en plus de la sauvegarde à chaque évènement Discord reçu. Cela couvre les périodes
+    /// sans évènement pendant lesquelles la base est modifiée, par exemple par le thread RSS.
+    ///
+    /// Désactivée par défaut. Rien n’est écrit si la base n’a pas changé depuis la dernière
+    /// sauvegarde. Un intervalle inférieur à une seconde est traité comme une seconde.
+    pub fn autosave_interval(mut self, intervalle: Duration) -> Self {
+        self.autosave_interval = Some(intervalle);
+        self
+    }
+
+    /// Active la journalisation, à chaque mise à jour d’un salon d’affichage, du nombre de messages
+    /// édités, supprimés, créés et laissés intacts. Désactivée par défaut.
+    ///
+    /// Le bilan est émis au niveau `info` par `tracing`, avec les champs `chan`, `edites`,
+    /// `supprimes`, `crees` et `intacts`. Cela permet de repérer les salons dont tous les messages
+    /// sont recréés à chaque mise à jour.
+    pub fn debug_affichans(mut self, debug: bool) -> Self {
+        self.debug_affichans = debug;
+        self
+    }
+
+    /// Définit le nombre maximal d’objets sauvegardés par une même entrée de l’historique des
+    /// modifications (voir [`Bot::archive`]). Par défaut, cette limite est de 10 000 objets.
+    ///
+    /// Chaque objet modifié est cloné dans l’historique, qui conserve les [`HISTORY_DEPTH`]
+    /// dernières modifications : une modification portant sur une grande partie de la base
+    /// (purge, modification en lot) peut donc en multiplier l’occupation mémoire. Au-delà de la
+    /// limite, la modification n’est pas annulable et l’historique est vidé. Augmenter la limite
+    /// rend annulables des modifications plus importantes au prix de la mémoire utilisée et de la
+    /// taille de la sauvegarde, dans laquelle l’historique est enregistré (voir [`Bot::annuler`]).
+    pub fn history_max_objects(mut self, max: usize) -> Self {
+        self.history_max_objects = max;
+        self
+    }
+
+    /// Définit le délai d’expiration des interactions attendues par le bot : boutons de
+    /// confirmation ([`tools::confirm`]) et boutons de navigation des messages à plusieurs pages
+    /// ([`Bot::send_embed`]), qui sont grisés une fois le délai écoulé.
+    ///
+    /// Par défaut, ce délai est d’une minute. Les boutons des messages d’objets des salons
+    /// d’affichage (voir [`Object::get_buttons`]) n’expirent pas et ne sont pas concernés.
+    pub fn interaction_timeout(mut self, timeout: Duration) -> Self {
+        self.interaction_timeout = timeout;
+        self
+    }
+
+    /// Définit le comportement du démarrage lorsqu’un salon absolu (voir [`Bot::setup`]) ne peut
+    /// pas être chargé. Si `skip` vaut `true`, l’erreur est loggée et le bot démarre sans ce
+    /// salon, qui reste rechargeable par [`Bot::load_absolute_chan`] ; sinon (par défaut),
+    /// l’erreur est loggée et le bot se déconnecte de Discord, terminant [`Client::start`].
+    ///
+    /// Ignoré si une politique de démarrage est définie par [`Bot::startup_policy`].
+    pub fn skip_invalid_absolute_chans(mut self, skip: bool) -> Self {
+        self.skip_invalid_absolute_chans = skip;
+        self
+    }
+
+    /// Rend éphémères (visibles du seul auteur de la commande) les réponses des commandes de
+    /// lecture, c’est-à-dire de permission [`Permission::READ`] : recherches, listes, affichage
+    /// d’objets… Utile pour ne pas encombrer les salons de commandes partagés.
+    ///
+    /// Par défaut, les réponses sont publiques. Voir [`Bot::is_ephemeral_reply`].
+    pub fn ephemeral_read_replies(mut self, ephemeral: bool) -> Self {
+        self.ephemeral_read_replies = ephemeral;
+        self
+    }
+
+    /// Définit le nombre maximal de résultats que la commande `rechercher` affiche en entier
+    /// (voir [`Object::get_reply`]), chacun dans sa propre réponse. Au-delà, les résultats sont
+    /// affichés sous forme de liste paginée.
+    ///
+    /// Par défaut, ce seuil est de 3. Avec un seuil de 0, les résultats sont toujours affichés en
+    /// liste.
+    pub fn inline_result_threshold(mut self, threshold: usize) -> Self {
+        self.inline_result_threshold = threshold;
+        self
+    }
+
+    /// Définit l’activité (statut) affichée par le bot une fois le chargement terminé. Pendant le
+    /// chargement, l’activité « Chargement des salons… » est affichée.
+    ///
+    /// Par défaut, le bot « joue à critiquer ».
+    pub fn activity(mut self, activity: ActivityData) -> Self {
+        self.activity = activity;
+        self
+    }
+
+    /// Définit une activité dynamique : une fois le chargement terminé, le statut du bot est
+    /// remplacé par le texte renvoyé par la fonction donnée, recalculé toutes les cinq minutes
+    /// (par exemple « 12 fils en attente »). Remplace l’activité de [`Bot::activity`].
+    ///
+    /// La fonction est appelée avec le verrou sur le bot tenu : elle doit être rapide.
+    pub fn dynamic_activity(mut self, f: Box<DynamicActivity<T>>) -> Self {
+        self.dynamic_activity = Some(Arc::from(f));
+        self
+    }
+
+    /// Définit le fuseau horaire utilisé pour interpréter les dates saisies dans les commandes
+    /// (`set_date`, `recherche_avancee`) et disponible pour formater les dates affichées (voir
+    /// [`Bot::get_timezone`] et [`tools::Timezone`]).
+    ///
+    /// Par défaut, UTC. Un fuseau de `chrono-tz` gère l’heure d’été :
+    ///
+    /// ```ignore
+    /// bot.timezone(chrono_tz::Europe::Paris)
+    /// ```
+    pub fn timezone<Tz: chrono::TimeZone + Send + Sync + 'static>(mut self, tz: Tz) -> Self {
+        self.timezone = tools::Timezone::new(tz);
+        self
+    }
+
+    /// Renvoie le fuseau horaire défini par [`Bot::timezone`].
+    pub fn get_timezone(&self) -> &tools::Timezone {
+        &self.timezone
+    }
+
+    /// Regroupe les mises à jour des salons d’affichage demandées en rafale : au lieu d’une mise
+    /// à jour à la fin du traitement de chaque évènement, une seule passe est faite une fois la
+    /// fenêtre donnée écoulée après la première demande (par exemple 500 ms). Les demandes faites
+    /// pendant la fenêtre sont traitées par cette même passe.
+    ///
+    /// Désactivé par défaut. Les demandes passent par [`Bot::request_affichan_update`] ; les
+    /// commandes de la bibliothèque, les boutons et les réactions y ont alors recours au lieu de
+    /// mettre à jour les salons eux-mêmes, sauf `/update_affichans` et les commandes qui ont besoin
+    /// de salons à jour (`/orphelins`, `/reset_affichans`). [`Bot::update_affichans`] reste
+    /// disponible pour une mise à jour immédiate.
+    pub fn affichan_debounce(mut self, fenetre: Duration) -> Self {
+        self.affichan_debounce = Some(fenetre);
+        self
+    }
+
+    /// Renvoie le seuil défini par [`Bot::inline_result_threshold`].
+    pub fn get_inline_result_threshold(&self) -> usize {
+        self.inline_result_threshold
+    }
+
+    /// Définit la politique appliquée aux erreurs de chargement lors du démarrage (voir
+    /// [`StartupPolicy`]) : salons d’affichage, salons absolus et salon des logs. En cas d’arrêt, le
+    /// bot se déconnecte de Discord, terminant [`Client::start`] ; en mode tolérant, les salons
+    /// d’affichage impossibles à charger sont retirés du bot. Leurs données sauvegardées (messages
+    /// et objets forcés) sont conservées dans la sauvegarde jusqu’au prochain démarrage où ils se
+    /// chargent.
+    ///
+    /// Sans appel à cette méthode, le comportement historique est conservé : une erreur sur un
+    /// salon d’affichage arrête le bot, une erreur sur le salon des logs est seulement loggée, et
+    /// une erreur sur un salon absolu dépend de [`Bot::skip_invalid_absolute_chans`].
+    pub fn startup_policy(mut self, policy: StartupPolicy) -> Self {
+        self.startup_policy = Some(policy);
+        self
+    }
+
+    /* Indique si une erreur de chargement au démarrage doit arrêter le bot, d’après la politique
+       de démarrage ou, à défaut, le comportement historique donné. */
+    fn _startup_error_is_fatal(&self, historique: bool) -> bool {
+        match self.startup_policy {
+            Some(StartupPolicy::Strict) => true,
+            Some(StartupPolicy::Tolerant) => false,
+            None => historique
+        }
+    }
+
+    /// Renvoie le délai d’expiration des interactions (voir [`Bot::interaction_timeout`]).
+    pub fn get_interaction_timeout(&self) -> Duration {
+        self.interaction_timeout
+    }
+
+    /// Définit le format des messages envoyés par [`Bot::log`] (voir [`LogStyle`]).
+    ///
+    /// Par défaut, les logs sont envoyés en texte brut.
+    pub fn log_style(mut self, style: LogStyle) -> Self {
+        self.log_style = style;
+        self
+    }
+
+    /// Définit le thème des embeds envoyés par la bibliothèque (voir [`Theme`]).
+    ///
+    /// Par défaut, les couleurs historiques de la bibliothèque sont utilisées.
+    pub fn theme(mut self, theme: Theme) -> Self {
+        self.theme = theme;
+        self
+    }
+
+    /// Renvoie le thème des embeds de la bibliothèque (voir [`Bot::theme`]).
+    pub fn get_theme(&self) -> &Theme {
+        &self.theme
+    }
+
+    /// Envoie un message dans le salon des logs, s’il est défini (voir [`Bot::set_log`]), au
+    /// format défini par [`Bot::log_style`].
     pub async fn log(&self, ctx: &impl CacheHttp, text: String) -> Result<(), ErrType> {
+        match self.log_style {
+            LogStyle::Text => if let Some(PreloadedChannel::Loaded(log)) = &self.log {
+                log.say(ctx, text).await?;
+            },
+            LogStyle::Embed => self.log_embed(ctx, self.theme.neutral_embed()
+                .description(text)
+                .timestamp(Timestamp::now())).await?
+        }
+        Ok(())
+    }
+
+    /// Envoie un embed dans le salon des logs, s’il est défini (voir [`Bot::set_log`]),
+    /// indépendamment de [`Bot::log_style`].
+    pub async fn log_embed(&self, ctx: &impl CacheHttp, embed: CreateEmbed) -> Result<(), ErrType> {
         if let Some(PreloadedChannel::Loaded(log)) = &self.log {
-            log.say(ctx, text).await?;
+            log.send_message(ctx, CreateMessage::new().embed(embed)).await?;
         }
         Ok(())
     }
 
     /* Affiche la page suivante ou précédente d’un multimessage après appui sur un bouton, utilisé dans handle_interaction */
     async fn _multimessage_bouton(&mut self, id: String, next: i32, ctx: &SerenityContext, interaction: &mut ComponentInteraction) -> serenity::all::Result<()> {
-        if let Some(&position) = self.mmpositions.get(&id) {
-            let new_pos: usize = ((position as i32) + next) as usize;
-            self.mmpositions.insert(id.clone(), new_pos);
+        /* Le multimessage est retrouvé d’après le message cliqué, à défaut d’après le bouton */
+        let id = self.mm_messages.get(&interaction.message.id).cloned().unwrap_or(id);
+        if self.multimessages.get(&id).is_some_and(|multimessage| multimessage.expiration <= Utc::now()) {
+            self._forget_multimessage(&id);
+        }
+        if let Some(multimessage) = self.multimessages.get_mut(&id) {
+            let derniere = multimessage.pages.len() - 1;
+            let new_pos = (multimessage.position as i64 + next as i64).clamp(0, derniere as i64) as usize;
+            multimessage.position = new_pos;
             interaction.create_response(ctx, CreateInteractionResponse::UpdateMessage(
                 CreateInteractionResponseMessage::new()
-                    .embed(self.multimessages.get(&id).unwrap()[new_pos].clone())
+                    .embed(multimessage.pages[new_pos].clone())
                     .button(CreateButton::new(id.clone() + "-p").label("Précédent")
                         .disabled(new_pos == 0)
                         .style(ButtonStyle::Secondary))
                     .button(CreateButton::new(id.clone() + "-n").label("Suivant")
-                        .disabled(new_pos == self.multimessages.get(&id).unwrap().len() - 1)
+                        .disabled(new_pos == derniere)
                         .style(ButtonStyle::Secondary)))
             ).await
         } else {
-            /* Multimessage absent: bot reboot? */
+            /* Multimessage absent ou expiré */
             interaction.create_response(ctx, CreateInteractionResponse::Acknowledge).await?;
             /* Grise les boutons, puisqu’on ne peut plus trouver les autres pages */
             interaction.message.edit(ctx, EditMessage::new()
-                .button(CreateButton::new(id.clone() + "-p")
-                    .label("Précédent")
-                    .disabled(true)
-                    .style(ButtonStyle::Secondary))
-                .button(CreateButton::new(id.clone() + "-n")
-                    .label("Suivant")
-                    .disabled(true)
-                    .style(ButtonStyle::Secondary)
-                )).await
+                .components(vec![_multimessage_boutons_grises(&id)])).await
         }
     }
 
+    /* Associe à un multimessage le message Discord qui le porte, une fois envoyé */
+    fn _register_multimessage(&mut self, id: &str, (channel, message): (ChannelId, MessageId)) {
+        if let Some(multimessage) = self.multimessages.get_mut(id) {
+            multimessage.message = Some((channel, message));
+            self.mm_messages.insert(message, id.to_string());
+        }
+    }
+
+    /* Oublie les pages d’un multimessage et son message Discord */
+    fn _forget_multimessage(&mut self, id: &str) {
+        if let Some((_, message)) = self.multimessages.remove(id).and_then(|multimessage| multimessage.message) {
+            self.mm_messages.remove(&message);
+        }
+    }
+
+    /* Cherche l’objet visé par une réaction, dans les salons d’affichage puis dans le pied de
+       page du message s’il a été envoyé par le bot. Le verrou n’est pas tenu pendant la
+       récupération du message auprès de Discord. Utilisé dans une closure dans new. */
+    async fn _reaction_object(
+        ctx: &SerenityContext,
+        reaction: &Reaction,
+        data: &DataType<T>
+    ) -> Result<Option<u64>, ErrType> {
+        let (self_id, object_id) = {
+            let bot = data.lock().await;
+            if reaction.user_id.is_none() || reaction.user_id == bot.self_id {
+                return Ok(None);
+            }
+            (bot.self_id, bot.affichans.iter().find_map(|affichan| affichan.object_for_message(&reaction.message_id)))
+        };
+        if object_id.is_some() {
+            return Ok(object_id);
+        }
+        let message = reaction.message(ctx).await?;
+        Ok(if Some(message.author.id) == self_id {
+            message.embeds.first().and_then(|embed| embed.footer.as_ref())
+                .and_then(|footer| T::parse_footer_id(&footer.text))
+        } else {
+            None
+        })
+    }
+
+    /* Gère les réactions aux messages d’objets, l’objet visé ayant été cherché par
+       _reaction_object. Utilisé dans une closure dans new. */
+    async fn handle_reaction(
+        &mut self,
+        ctx: &SerenityContext,
+        reaction: &Reaction,
+        object_id: Option<u64>
+    ) -> Result<(), ErrType> {
+        if let Some(object_id) = object_id.filter(|object_id| self.database.contains_key(object_id)) {
+            T::reactions(ctx, reaction, object_id, self).await?;
+            self._update_affichans_after_change(ctx, None).await?;
+        }
+        Ok(())
+    }
+
     /* Gère les boutons, utilisé dans une closure dans new */
     async fn handle_interaction(&mut self, ctx: &SerenityContext, interaction: &mut ComponentInteraction) -> Result<(), ErrType> {
-        if interaction.data.custom_id.starts_with("mm") {
-            let id = interaction.data.custom_id.split("-").next()
-                .ok_or(ErrType::InteractionIDError(interaction.data.custom_id.clone(), interaction.message.id.get()))?.to_string();
-            let next: i32 = if interaction.data.custom_id.split("-").last()
-                .ok_or(ErrType::InteractionIDError(interaction.data.custom_id.clone(), interaction.message.id.get()))? == "n" {1} else {-1};
-            self._multimessage_bouton(id, next, ctx, interaction).await?;
+        if interaction.data.custom_id.starts_with(tools::CONFIRM_PREFIX) {
+            /* Bouton de confirmation, traité par le collecteur de tools::confirm */
+        } else if interaction.data.custom_id.starts_with(tools::MULTIMESSAGE_PREFIX)
+            || tools::_is_legacy_multimessage_id(&interaction.data.custom_id) {
+            let (id, sens) = interaction.data.custom_id.rsplit_once('-')
+                .ok_or(ErrType::InteractionIDError(interaction.data.custom_id.clone(), interaction.message.id.get()))?;
+            let next: i32 = if sens == "n" {1} else {-1};
+            self._multimessage_bouton(id.to_string(), next, ctx, interaction).await?;
         } else {
-            if let Err(e) = T::buttons(ctx, interaction, self).await {
+            let resultat = if interaction.data.custom_id.starts_with(tools::MODAL_PREFIX) {
+                self._open_modal(ctx, interaction).await
+            } else {
+                T::buttons(ctx, interaction, self).await
+            };
+            if let Err(e) = resultat {
+                self._signal_interaction_error(ctx, interaction).await;
                 match e {
                     ErrType::ObjectNotFound(obj) => {
-                        eprintln!("Objet {obj} non trouvé associé au bouton {}. Suppression du message.", interaction.data.custom_id);
+                        warn!("Objet {obj} non trouvé associé au bouton {}. Suppression du message.", interaction.data.custom_id);
                         interaction.message.delete(ctx).await?;
                     },
-                    ErrType::InteractionIDError(_, _) => eprintln!("{e}"), /* Tant pis, on va pas faire crash le bot pour un bouton mal formé. */
+                    ErrType::InteractionIDError(_, _) => warn!("{e}"), /* Tant pis, on va pas faire crash le bot pour un bouton mal formé. */
                     _ => return Err(e)
                 }
 
             } else {
-                self.update_affichans(ctx).await?;
+                self._update_affichans_after_change(ctx, None).await?;
+            }
+        }
+        Ok(())
+    }
+
+    /* Signale les boutons d’objets (Object::get_buttons) utilisant un identifiant réservé par la
+       bibliothèque (voir tools::is_reserved_id), qui ne seraient jamais transmis à T::buttons.
+       Chaque identifiant n’est signalé qu’une fois. */
+    fn _check_button_ids(&self) {
+        let mut reserves: HashMap<String, usize> = HashMap::new();
+        for object in self.database.values() {
+            for custom_id in tools::reserved_ids(&object.get_buttons()) {
+                *reserves.entry(custom_id).or_default() += 1;
+            }
+        }
+        for (custom_id, nombre) in reserves {
+            warn!("L’identifiant de bouton {custom_id}, utilisé par {nombre} objet(s), est réservé par la bibliothèque : \
+                   ces boutons ne seront pas transmis à Object::buttons.");
+        }
+    }
+
+    /* Ouvre le formulaire d’édition (Object::modal) de l’objet désigné par un bouton créé par
+       tools::modal_button. Le formulaire reprend l’identifiant du bouton. */
+    async fn _open_modal(&self, ctx: &SerenityContext, interaction: &ComponentInteraction) -> Result<(), ErrType> {
+        let custom_id = &interaction.data.custom_id;
+        let object_id: u64 = custom_id[tools::MODAL_PREFIX.len()..].parse()
+            .map_err(|_| ErrType::InteractionIDError(custom_id.clone(), interaction.message.id.get()))?;
+        let object = self.database.get(&object_id).ok_or(ErrType::ObjectNotFound(object_id.to_string()))?;
+        let modal = object.modal(custom_id.clone())
+            .ok_or(ErrType::InteractionIDError(custom_id.clone(), interaction.message.id.get()))?;
+        interaction.create_response(ctx, CreateInteractionResponse::Modal(modal)).await?;
+        Ok(())
+    }
+
+    /* Traite un formulaire d’édition rempli (voir Object::apply_modal), utilisé dans une closure
+       dans new. La modification est faite sur une copie de l’objet, remplacée dans la base
+       seulement si elle a réussi. */
+    async fn handle_modal(&mut self, ctx: &SerenityContext, modal: &ModalInteraction) -> Result<(), ErrType> {
+        let Some(object_id) = modal.data.custom_id.strip_prefix(tools::MODAL_PREFIX)
+            .and_then(|id| id.parse::<u64>().ok()) else {
+            return Ok(());
+        };
+        let values: HashMap<String, String> = modal.data.components.iter()
+            .flat_map(|row| row.components.iter())
+            .filter_map(|component| match component {
+                ActionRowComponent::InputText(input) =>
+                    Some((input.custom_id.clone(), input.value.clone().unwrap_or_default())),
+                _ => None
+            }).collect();
+        let resultat = match self.database.get(&object_id) {
+            Some(object) => {
+                let mut modifie = object.clone();
+                modifie.apply_modal(&values).map(|_| modifie)
+            },
+            None => Err(ErrType::ObjectNotFound(object_id.to_string()))
+        };
+        let (message, log) = match resultat {
+            Ok(modifie) => {
+                let nom = modifie.get_name().clone();
+                let details = tools::describe_diff(&self.database[&object_id], &modifie)
+                    .map_or_else(String::new, |details| format!("\n{details}"));
+                self.archive(vec![object_id]);
+                self.database.insert(object_id, modifie);
+                self.mark_modified_by(object_id, modal.user.id);
+                (self.tr("modal.ok", &[("nom", &nom)]), Some(format!(
+                    "{} a modifié l'objet {nom} (id: {object_id}) par formulaire.{details}",
+                    tools::user_desc(&modal.user)
+                )))
+            },
+            Err(ErrType::CommandUseError(message)) => (message, None),
+            Err(e) => {
+                warn!("Erreur lors du traitement du formulaire {} : {e}", modal.data.custom_id);
+                (self.tr("interaction.erreur", &[]), None)
             }
+        };
+        modal.create_response(ctx, CreateInteractionResponse::Message(
+            CreateInteractionResponseMessage::new().content(message).ephemeral(true)
+        )).await?;
+        if let Some(log) = log {
+            self.log(ctx, log).await?;
         }
         Ok(())
     }
 
+    /* Répond à l’interaction par un message éphémère signalant une erreur, pour que l’utilisateur
+       ne reste pas sans réponse. Si T::buttons a déjà répondu, le message est envoyé en suivi de
+       la réponse. Les échecs sont seulement journalisés, l’erreur d’origine étant plus utile. */
+    async fn _signal_interaction_error(&self, ctx: &SerenityContext, interaction: &ComponentInteraction) {
+        let message = self.tr("interaction.erreur", &[]);
+        if interaction.create_response(ctx, CreateInteractionResponse::Message(
+            CreateInteractionResponseMessage::new().content(&message).ephemeral(true)
+        )).await.is_err() {
+            if let Err(e) = interaction.create_followup(ctx,
+                CreateInteractionResponseFollowup::new().content(message).ephemeral(true)).await {
+                warn!("Impossible de signaler l’erreur de l’interaction {} : {e}", interaction.data.custom_id);
+            }
+        }
+    }
+
     /// Sauvegarde les écrits dont les identifiants sont donnés.
     ///
     /// Chaque appel à cette fonction crée une nouvelle entrée dans l’historique qui sera
-    /// restaurée à chaque appel à [`Bot::annuler`]. Si l’historique contient plus de 5 éléments,
-    /// le plus ancien est supprimé.
+    /// restaurée à chaque appel à [`Bot::annuler`]. Si l’historique contient plus de
+    /// [`HISTORY_DEPTH`] éléments, le plus ancien est supprimé.
+    ///
+    /// Si plus d’objets que la limite définie par [`Bot::history_max_objects`] sont donnés, ils ne
+    /// sont pas sauvegardés : la modification n’est pas annulable et l’historique est vidé, les
+    /// entrées précédentes ne pouvant plus être restaurées de manière cohérente.
     ///
     /// Cette fonction règle le drapeau `Bot.update_affichans`
     /// à `true` étant donné que cette fonction doit être systématiquement appelée avant chaque
     /// modification. Cela permet d’éviter de répéter ces deux associations d’actions qui vont
     /// ensemble.
     pub fn archive(&mut self, ids: Vec<u64>){
-        if !ids.is_empty() {
-            if self.history.len() >= 5 {
+        if ids.len() > self.history_max_objects {
+            warn!("Modification de {} objets, au-delà de la limite de {} : modification non annulable, historique vidé.",
+                ids.len(), self.history_max_objects);
+            self.history.clear();
+        } else if !ids.is_empty() {
+            if self.history.len() >= HISTORY_DEPTH {
                 self.history.pop_back();
             }
             self.history.push_front(ids.into_iter().map(
@@ -471,48 +1855,477 @@ impl<T: Object> Bot<T> {
                 }
             ).collect());
         }
-        self.update_affichans = true; // Parce que si on archive, c’est qu’on modifie un truc.
+        self.request_affichan_update(); // Parce que si on archive, c’est qu’on modifie un truc.
 
     }
 
+    /// Supprime tous les objets respectant le prédicat donné et renvoie leurs identifiants.
+    ///
+    /// La suppression est archivée en une seule entrée de l’historique (voir [`Bot::archive`]) :
+    /// elle est donc annulable en une fois par [`Bot::annuler`], quel que soit le nombre d’objets
+    /// supprimés. La mise à jour des salons d’affichage est également demandée.
+    pub fn purge_where(&mut self, pred: impl Fn(&T) -> bool) -> Vec<u64> {
+        let ids: Vec<u64> = self.database.iter()
+            .filter(|(_, object)| pred(object))
+            .map(|(&id, _)| id).collect();
+        self.archive(ids.clone());
+        ids.iter().for_each(|id| {
+            self.database.remove(id);
+            self._update_search_index(*id);
+            self.notify_change(ChangeEvent::Deleted(*id));
+        });
+        ids
+    }
+
+    /// Ajoute un objet à la base de données, ou remplace l’objet de même identifiant
+    /// ([`Object::get_id`]), et renvoie l’objet remplacé s’il existait.
+    ///
+    /// L’ajout est archivé (voir [`Bot::archive`]) et donc annulable par [`Bot::annuler`]. Un
+    /// objet remplacé est marqué comme modifié (voir [`Bot::mark_modified`]).
+    ///
+    /// Si l’objet gère ses dates ([`Object::as_timestamped_mut`]), sa date de modification est
+    /// définie à maintenant, et sa date de création aussi s’il est nouveau et n’en a pas. Un objet
+    /// remplacé conserve la date de création de l’objet qu’il remplace.
+    pub fn insert(&mut self, mut object: T) -> Option<T> {
+        let id = object.get_id();
+        self.archive(vec![id]);
+        if let Some(dates) = object.as_timestamped_mut() {
+            let maintenant = Utc::now();
+            /* Un objet remplacé conserve sa date de création */
+            let creation = self.database.get(&id)
+                .and_then(|ancien| ancien.as_timestamped())
+                .and_then(Timestamped::created_at);
+            dates.set_created_at(creation.or(dates.created_at()).unwrap_or(maintenant));
+            dates.set_updated_at(maintenant);
+        }
+        let ancien = self.database.insert(id, object);
+        self._update_search_index(id);
+        if ancien.is_some() {
+            self._mark_modified(id);
+        }
+        self.notify_change(if ancien.is_some() { ChangeEvent::Modified(id) } else { ChangeEvent::Created(id) });
+        ancien
+    }
+
+    /// Supprime l’objet d’identifiant donné de la base de données et le renvoie, ou renvoie
+    /// [`None`] s’il n’existe pas.
+    ///
+    /// La suppression est archivée (voir [`Bot::archive`]) et donc annulable par [`Bot::annuler`].
+    pub fn remove(&mut self, id: u64) -> Option<T> {
+        if !self.database.contains_key(&id) {
+            return None;
+        }
+        self.archive(vec![id]);
+        let object = self.database.remove(&id);
+        self._update_search_index(id);
+        self.notify_change(ChangeEvent::Deleted(id));
+        object
+    }
+
+    /// Renvoie un récepteur des changements de la base de données (voir [`ChangeEvent`]), permettant
+    /// de brancher des intégrations externes (webhook, mise à jour d’un site…).
+    ///
+    /// Les changements sont émis par les méthodes du bot modifiant la base de données
+    /// ([`Bot::insert`], [`Bot::remove`], [`Bot::mark_modified`], [`Bot::purge_where`],
+    /// [`Bot::annuler`]) et par les commandes par défaut. Les modifications directes de
+    /// [`Bot::database`] ne sont pas détectées : appeler alors [`Bot::notify_change`].
+    ///
+    /// L’émission ne bloque jamais le bot : si personne n’écoute, les changements sont perdus, et
+    /// un récepteur trop lent perd les changements les plus anciens (voir
+    /// [`broadcast::error::RecvError::Lagged`]).
+    pub fn subscribe(&self) -> broadcast::Receiver<ChangeEvent> {
+        self.changes.subscribe()
+    }
+
+    /// Émet un changement de la base de données aux récepteurs de [`Bot::subscribe`]. Ne bloque pas
+    /// et ne fait rien si personne n’écoute.
+    pub fn notify_change(&self, event: ChangeEvent) {
+        let _ = self.changes.send(event);
+    }
+
+    /// Demande une mise à jour des salons d’affichage. Sans [`Bot::affichan_debounce`], elle est
+    /// faite à la fin du traitement de l’évènement Discord en cours ; sinon, elle est regroupée
+    /// avec les autres demandes de la fenêtre.
+    ///
+    /// Appelée par [`Bot::archive`] et [`Bot::mark_modified`]. Équivaut à passer
+    /// `Bot.update_affichans` à `true`, en réveillant en plus la mise à jour groupée.
+    pub fn request_affichan_update(&mut self) {
+        self.update_affichans = true;
+        self.affichan_notify.notify_one();
+    }
+
+    /* Mise à jour des salons d’affichage à la fin d’une commande, d’un bouton ou d’une réaction :
+       regroupée par Bot::request_affichan_update si Bot::affichan_debounce est défini, immédiate
+       sinon (limitée aux salons concernés par l’objet donné, s’il y en a un). */
+    pub(crate) async fn _update_affichans_after_change(
+        &mut self,
+        ctx: &SerenityContext,
+        object_id: Option<u64>
+    ) -> Result<(), ErrType> {
+        if self.affichan_debounce.is_some() {
+            self.request_affichan_update();
+            Ok(())
+        } else if let Some(object_id) = object_id {
+            self.update_affichan_for(ctx, object_id).await
+        } else {
+            self.update_affichans(ctx).await
+        }
+    }
+
+    /// Marque l’objet d’identifiant donné comme modifié ([`Object::set_modified`]), appelle
+    /// [`Object::on_modified`] puis demande la mise à jour des salons d’affichage (voir
+    /// [`Bot::update_affichans`]). Ne fait rien si l’objet n’existe pas.
+    ///
+    /// Cette méthode est à privilégier à un appel direct à [`Object::set_modified`] après
+    /// toute modification d’un objet. Elle met également à jour la date de modification des
+    /// objets gérant leurs dates (voir [`object::Timestamped`]).
+    pub fn mark_modified(&mut self, id: u64) {
+        if self._mark_modified(id) {
+            self.notify_change(ChangeEvent::Modified(id));
+        }
+    }
+
+    /// Équivalent de [`Bot::mark_modified`] enregistrant aussi l’utilisateur ayant modifié l’objet,
+    /// pour les objets qui le gèrent (voir [`object::Editable`]). À utiliser pour les
+    /// modifications faites à la demande d’un utilisateur, par exemple dans les commandes.
+    pub fn mark_modified_by(&mut self, id: u64, user: UserId) {
+        if let Some(edition) = self.database.get_mut(&id).and_then(Object::as_editable_mut) {
+            edition.set_last_editor(user);
+        }
+        self.mark_modified(id);
+    }
+
+    /* Bot::mark_modified sans émission de changement. Renvoie false si l’objet n’existe pas. */
+    fn _mark_modified(&mut self, id: u64) -> bool {
+        if let Some(object) = self.database.get_mut(&id) {
+            object.set_modified(true);
+            if let Some(dates) = object.as_timestamped_mut() {
+                dates.set_updated_at(Utc::now());
+            }
+            object.on_modified();
+            self._update_search_index(id);
+            self.request_affichan_update();
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Renvoie les identifiants des objets dont le nom est déjà porté par un autre objet de la base
+    /// de données, triés par ordre croissant. Pour chaque nom en double, l’objet d’identifiant le
+    /// plus petit est conservé et n’apparaît donc pas dans le résultat.
+    ///
+    /// Les noms sont comparés selon [`Bot::duplicate_matching`] : par défaut, « La Fondation » et
+    /// « la  fondation » sont des doublons.
+    ///
+    /// Cette méthode ne modifie pas la base de données ; voir la commande `/doublons` pour la
+    /// suppression.
+    pub fn find_duplicates(&self) -> Vec<u64> {
+        let mut ids: Vec<&u64> = self.database.keys().collect();
+        ids.sort();
+        let mut names = HashSet::new();
+        ids.into_iter()
+            .filter(|id| !names.insert(tools::duplicate_key(self.database[*id].get_name(),
+                                                             self.duplicate_matching, &self.normalize_options)))
+            .copied().collect()
+    }
+
+    /// Renvoie les identifiants de tous les objets de la base de données, triés par date du plus
+    /// récent au plus ancien (voir [`tools::sort_by_date`], qui tient aussi compte de l’épinglage
+    /// et de la priorité).
+    pub fn sorted_ids(&self) -> Vec<u64> {
+        tools::sort_by_date(self.database.iter().collect()).into_iter().map(|(&id, _)| id).collect()
+    }
+
+    /// Renvoie les identifiants de tous les objets de la base de données, triés selon la fonction
+    /// de comparaison donnée. Le tri est stable, mais l’ordre initial des objets n’est pas défini.
+    ///
+    /// Exemple : `bot.sorted_ids_by(|a, b| a.get_name().cmp(b.get_name()))` trie par nom.
+    pub fn sorted_ids_by(&self, mut compare: impl FnMut(&T, &T) -> std::cmp::Ordering) -> Vec<u64> {
+        let mut objects: Vec<(&u64, &T)> = self.database.iter().collect();
+        objects.sort_by(|(_, a), (_, b)| compare(a, b));
+        objects.into_iter().map(|(&id, _)| id).collect()
+    }
+
+    /// Renvoie un identifiant libre pour un nouvel objet : le plus grand identifiant de la base
+    /// de données plus un, ou `0` si la base est vide.
+    pub fn next_id(&self) -> u64 {
+        self.database.keys().max().map_or(0, |id| id + 1)
+    }
+
+    /// Renvoie, pour chaque salon d’affichage, l’identifiant de son salon Discord et le nombre
+    /// d’objets qu’il affiche, dans l’ordre de déclaration des salons d’affichage.
+    pub fn affichan_counts(&self) -> Vec<(u64, usize)> {
+        self.affichans.iter().map(|affichan| (affichan.get_chan_id(), affichan.object_count())).collect()
+    }
+
+    /// Renvoie `true` si le thread RSS, lancé par [`Bot::setup`], est en cours d’exécution.
+    pub fn rss_running(&self) -> bool {
+        self.rss_task.as_ref().is_some_and(|rss_task| !rss_task.is_finished())
+    }
+
+    /// Renvoie la date de la dernière exécution réussie de [`Object::maj_rss`] par le thread RSS,
+    /// ou [`None`] si aucune n’a encore réussi depuis le démarrage du bot.
+    pub fn last_rss_success(&self) -> Option<DateTime<Utc>> {
+        self.last_rss_success
+    }
+
+    /// Renvoie la date et le message de la dernière erreur de [`Object::maj_rss`] dans le thread
+    /// RSS, ou [`None`] si aucune erreur n’a eu lieu depuis le démarrage du bot. L’erreur est
+    /// conservée après une exécution réussie : comparer sa date à [`Bot::last_rss_success`].
+    pub fn last_rss_error(&self) -> Option<&(DateTime<Utc>, String)> {
+        self.last_rss_error.as_ref()
+    }
+
+    /// Renvoie le salon d’affichage dont le salon Discord a l’identifiant donné, s’il existe.
+    /// Permet d’inspecter son contenu en lecture seule (voir [`Affichan::iter_objects`]).
+    pub fn affichan_by_chan(&self, chan_id: u64) -> Option<&Affichan<T>> {
+        self.affichans.iter().find(|affichan| affichan.get_chan_id() == chan_id)
+    }
+
+    /// Renvoie l’identifiant de l’objet affiché par le message donné dans l’un des salons
+    /// d’affichage (voir [`Affichan::object_for_message`]), ou [`None`] si le message n’appartient
+    /// à aucun salon d’affichage.
+    pub fn object_for_message(&self, message_id: MessageId) -> Option<u64> {
+        self.affichans.iter().find_map(|affichan| affichan.object_for_message(&message_id))
+    }
+
+    /// Renvoie les identifiants des objets correspondant à la requête de recherche avancée donnée
+    /// (voir [`tools::parse_advanced_query`]), triés par date (voir [`tools::sort_by_date`]).
+    ///
+    /// Le nom est recherché par [`Bot::search`], les dates sont comparées à [`Object::get_date`]
+    /// (les objets sans date sont exclus dès qu’un filtre de date est donné) et les autres filtres
+    /// sont interprétés par [`Object::matches_filter`]. Renvoie en erreur la clé du premier filtre
+    /// non reconnu.
+    pub fn advanced_search(&self, query: &tools::AdvancedQuery) -> Result<Vec<u64>, String> {
+        let candidats: Vec<&u64> = if query.nom.is_empty() {
+            self.database.keys().collect()
+        } else {
+            self.search(&query.nom)
+        };
+        let mut resultats = Vec::new();
+        for id in candidats {
+            let object = &self.database[id];
+            if query.avant.is_some() || query.apres.is_some() {
+                if !object.has_date() {
+                    continue;
+                }
+                let date = object.get_date().unix_timestamp();
+                if query.avant.is_some_and(|avant| date >= avant.unix_timestamp())
+                    || query.apres.is_some_and(|apres| date < apres.unix_timestamp()) {
+                    continue;
+                }
+            }
+            let mut accepte = true;
+            for (cle, valeur) in &query.filtres {
+                match object.matches_filter(cle, valeur) {
+                    Some(correspond) => accepte &= correspond,
+                    None => return Err(cle.clone())
+                }
+            }
+            if accepte {
+                resultats.push((id, object));
+            }
+        }
+        Ok(tools::sort_by_date(resultats).into_iter().map(|(&id, _)| id).collect())
+    }
+
+    /// Renvoie les identifiants des objets modifiés depuis la date donnée, d’après leur date de
+    /// dernière modification (voir [`object::Timestamped`]), du plus récemment modifié au plus
+    /// ancien. Les objets ne gérant pas leurs dates ne sont jamais renvoyés.
+    pub fn modified_since(&self, date: DateTime<Utc>) -> Vec<u64> {
+        let mut objects: Vec<(u64, DateTime<Utc>)> = self.database.iter()
+            .filter_map(|(&id, object)| object.as_timestamped()
+                .and_then(Timestamped::updated_at)
+                .filter(|modification| *modification >= date)
+                .map(|modification| (id, modification)))
+            .collect();
+        objects.sort_by(|(_, a), (_, b)| b.cmp(a));
+        objects.into_iter().map(|(id, _)| id).collect()
+    }
+
+    /// Renvoie les identifiants des objets de la base de données qui ne sont affichés dans aucun
+    /// salon d’affichage (voir [`Affichan::contains_object`]).
+    ///
+    /// Le résultat n’est fiable qu’après une mise à jour des salons d’affichage
+    /// ([`Bot::update_affichans`]).
+    pub fn orphans(&self) -> Vec<u64> {
+        self.database.keys()
+            .filter(|id| !self.affichans.iter().any(|affichan| affichan.contains_object(id)))
+            .copied().collect()
+    }
+
+    /// Renvoie les identifiants, triés par ordre croissant, des objets marqués comme modifiés
+    /// ([`Object::is_modified`]) dont les messages des salons d’affichage n’ont pas encore été
+    /// mis à jour par [`Bot::update_affichans`].
+    ///
+    /// Cette méthode ne modifie pas la base de données et permet de comprendre pourquoi un salon
+    /// d’affichage n’est pas à jour.
+    pub fn pending_modified(&self) -> Vec<u64> {
+        let mut ids: Vec<u64> = self.database.iter()
+            .filter(|(_, object)| object.is_modified())
+            .map(|(&id, _)| id)
+            .collect();
+        ids.sort_unstable();
+        ids
+    }
+
+    /// Renvoie les objets concernés par la prochaine annulation ([`Bot::annuler`]), ou [`None`] si
+    /// l’historique est vide. Chaque élément contient l’identifiant de l’objet, son état actuel
+    /// ([`None`] s’il n’existe plus) et l’état qui serait restauré ([`None`] si l’objet serait
+    /// supprimé).
+    pub fn undo_preview(&self) -> Option<Vec<UndoPreview<'_, T>>> {
+        self.history.front().map(|edit| edit.iter()
+            .map(|(id, ecrit)| (*id, self.database.get(id), ecrit.as_ref()))
+            .collect())
+    }
+
+    /// Renvoie le nombre de modifications actuellement annulables par [`Bot::annuler`].
+    pub fn history_len(&self) -> usize {
+        self.history.len()
+    }
+
+    /// Vide l’historique des modifications : plus aucune modification n’est annulable par
+    /// [`Bot::annuler`]. Renvoie le nombre d’entrées supprimées.
+    pub fn clear_history(&mut self) -> usize {
+        let taille = self.history.len();
+        self.history.clear();
+        taille
+    }
+
     /// Annule la dernière modification, renvie `false` si l’historique est vide.
     ///
-    /// L’historique ayant une profondeur maximum de 5, il n’est pas possible d’appeler plus de
-    /// cinq fois d’affilée cette méthode.
+    /// L’historique ayant une profondeur maximum de [`HISTORY_DEPTH`], il n’est pas possible
+    /// d’appeler cette méthode plus de [`HISTORY_DEPTH`] fois d’affilée. L’historique est
+    /// enregistré avec la base de données (voir [`Bot::save`]) et rechargé par [`Bot::setup`] :
+    /// une modification reste annulable après un redémarrage du bot.
     pub fn annuler(&mut self) -> bool {
         if let Some(edit) = self.history.pop_front() {
             edit.iter().for_each(|(id, ecrit)| match ecrit {
                 Some(e) => {
-                    self.database.insert(*id, e.clone());
-                    self.database.get_mut(&id).unwrap().set_modified(true);
+                    let existait = self.database.insert(*id, e.clone()).is_some();
+                    self._mark_modified(*id);
+                    self.notify_change(if existait { ChangeEvent::Modified(*id) } else { ChangeEvent::Created(*id) });
                 }
                 None => {
-                    self.database.remove(&id);
+                    if self.database.remove(id).is_some() {
+                        self._update_search_index(*id);
+                        self.notify_change(ChangeEvent::Deleted(*id));
+                    }
                 }
             });
-            self.update_affichans = true;
+            self.request_affichan_update();
             true
         } else {
             false
         }
     }
 
-    /// Sauvegarde la base de données dans son fichier de sauvegarde, au format YAML.
+    /// Sauvegarde la base de données par son moyen de stockage (voir [`Bot::storage`]).
+    ///
+    /// Par défaut, la base est sauvegardée dans son fichier de sauvegarde, au format YAML, par
+    /// [`YamlFileStorage`]. La sauvegarde précédente est alors conservée dans un fichier `.bak`
+    /// (même chemin suivi de `.bak`), restaurable par la commande `/restaurer_backup`.
+    ///
+    /// Si le stockage est incrémental, seuls les objets ajoutés, modifiés ou supprimés depuis
+    /// la dernière sauvegarde y sont écrits, ainsi que les métadonnées. Si ni les objets ni les
+    /// métadonnées n’ont changé depuis la dernière sauvegarde, rien n’est écrit : les sauvegardes
+    /// à chaque évènement et les sauvegardes périodiques (voir [`Bot::autosave_interval`]) ne
+    /// produisent donc pas d’écritures en double.
+    ///
+    /// Renvoie [`ErrType::NoneError`] si le bot n’a pas encore été initialisé par [`Bot::setup`].
     pub fn save(&self) -> Result<(), ErrType> {
-        let objects_out: Vec<Yaml> = self.database.iter().map(|(_, object)| object.serialize()).collect();
+        let resultat = self._save();
+        #[cfg(feature = "metrics")]
+        metrics::record_save(self.database.len(), resultat.is_ok());
+        resultat
+    }
+
+    /* Écriture effective de la sauvegarde, voir Bot::save */
+    fn _save(&self) -> Result<(), ErrType> {
+        let meta = Yaml::Hash(self._meta_yaml());
+        self.storage.as_ref().ok_or(ErrType::NoneError)?
+            .lock().unwrap_or_else(|e| e.into_inner())
+            .save(self.database.iter().map(|(&id, object)| (id, object.serialize())), &meta)
+    }
+
+    /* Métadonnées de la sauvegarde : tout sauf les objets de la base de données */
+    fn _meta_yaml(&self) -> yaml::Hash {
+        /* Les salons non chargés sont triés pour que l’empreinte de la sauvegarde ne dépende pas de
+           l’ordre de la table */
+        let mut unloaded: Vec<(&u64, &Yaml)> = self.unloaded_affichans.iter().collect();
+        unloaded.sort_by_key(|(chan_id, _)| **chan_id);
         let affichans_out =
             self.affichans.iter().map(|affichan| {(
                 Yaml::Integer(affichan.get_chan_id() as i64),
                 affichan.save()
-            )}).collect();
+            )}).chain(unloaded.into_iter().map(|(chan_id, data)| (Yaml::Integer(*chan_id as i64), data.clone())))
+            .collect();
         let mut yaml_out = yaml::Hash::new();
-        yaml_out.insert(Yaml::String("entries".into()), Yaml::Array(objects_out));
         yaml_out.insert(Yaml::String("last_rss_update".into()), Yaml::Integer(self.last_rss_update.timestamp()));
         yaml_out.insert(Yaml::String("affichans".into()), Yaml::Hash(affichans_out));
-        let mut out_str = String::new();
-        YamlEmitter::new(&mut out_str).dump(&Yaml::Hash(yaml_out))?;
-        fs::write(&self.data_file, &out_str)?;
-        Ok(())
+        /* Triés pour que l’empreinte de la sauvegarde ne dépende pas de l’ordre de la table */
+        let mut multimessages: Vec<(&String, &MultiMessage)> = self.multimessages.iter().collect();
+        multimessages.sort_by_key(|(id, _)| *id);
+        yaml_out.insert(Yaml::String("multimessages".into()), Yaml::Array(multimessages.into_iter()
+            .filter_map(|(id, multimessage)| multimessage.to_yaml(id)).collect()));
+        yaml_out.insert(Yaml::String("history".into()), self._history_yaml());
+        yaml_out
+    }
+
+    /* Historique des modifications sous forme sauvegardable, de la plus récente à la plus
+       ancienne. Chaque modification est une liste d’objets {id, object}, object étant Null pour
+       un objet créé par la modification. Sa taille est bornée par la profondeur de l’historique
+       et par Bot::history_max_objects. */
+    fn _history_yaml(&self) -> Yaml {
+        Yaml::Array(self.history.iter().map(|edit| Yaml::Array(edit.iter().map(|(id, object)| {
+            let mut entry = yaml::Hash::new();
+            entry.insert(Yaml::String("id".into()), Yaml::Integer(*id as i64));
+            entry.insert(Yaml::String("object".into()), object.as_ref().map_or(Yaml::Null, Object::serialize));
+            Yaml::Hash(entry)
+        }).collect())).collect())
+    }
+
+    /* Relit l’historique sauvegardé par Bot::_history_yaml. Les modifications suivant (plus
+       anciennes que) une modification illisible sont abandonnées, leur annulation n’ayant plus de
+       sens sans elle. Une sauvegarde sans historique donne un historique vide. */
+    fn _load_history(data: &Yaml) -> VecDeque<Vec<(u64, Option<T>)>> {
+        let mut history = VecDeque::new();
+        for edit in data["history"].as_vec().into_iter().flatten().take(HISTORY_DEPTH) {
+            let edit: Result<Vec<(u64, Option<T>)>, ErrType> = edit.as_vec().into_iter().flatten().map(|entry| {
+                let id = entry["id"].as_i64()
+                    .ok_or(ErrType::YamlParseError("Identifiant manquant dans l’historique.".to_string()))? as u64;
+                Ok((id, match &entry["object"] {
+                    Yaml::Null | Yaml::BadValue => None,
+                    object => Some(T::from_yaml(object)?)
+                }))
+            }).collect();
+            match edit {
+                Ok(edit) => history.push_back(edit),
+                Err(e) => {
+                    warn!("Historique des modifications partiellement chargé : {e}");
+                    break;
+                }
+            }
+        }
+        history
+    }
+
+    /* Charge la sauvegarde depuis le Storage, sous la même forme que le fichier YAML par défaut.
+       Renvoie None si le Storage est vide. Utilisé dans Bot::setup et Bot::_load_backup. */
+    fn _load_from_storage(storage: &dyn Storage) -> Result<Option<Vec<Yaml>>, ErrType> {
+        let entries = storage.load_all()?;
+        Ok(match storage.load_meta()? {
+            None if entries.is_empty() => None,
+            meta => {
+                let mut yaml_out = yaml::Hash::new();
+                yaml_out.insert(Yaml::String("entries".into()), Yaml::Array(entries));
+                if let Some(Yaml::Hash(meta)) = meta {
+                    yaml_out.extend(meta);
+                }
+                Some(vec![Yaml::Hash(yaml_out)])
+            }
+        })
     }
 
     /// Recherche un objet d’après son nom.
@@ -525,44 +2338,226 @@ impl<T: Object> Bot<T> {
     /// Exemple : Pour le titre « La Fondation SCP », les critères « fonda »,
     /// « scp » et « fonda scp » seront valides. Par contre, le critère
     /// « fondations » rejettera ce titre.
+    ///
+    /// Les mots sont comparés après normalisation (voir [`Bot::normalize_options`]).
+    ///
+    /// Un mot du critère précédé de `-` est un mot exclu : un objet est rejeté si l’un des mots de
+    /// son titre contient le mot exclu. Par exemple, « fondation -ancien » renvoie les objets dont
+    /// le titre contient « fondation » mais aucun mot contenant « ancien ». Un critère ne contenant
+    /// que des mots exclus renvoie tous les objets sauf ceux rejetés.
+    ///
+    /// La recherche passe par un index des mots normalisés des noms, mis à jour par les méthodes
+    /// modifiant la base de données (voir [`Bot::database`]).
     pub fn search(&self, critere: &str) -> Vec<&u64> {
-        self.database.iter().filter(|(_, object)|
-             critere.split(" ").fold(false, |corresponds, mot_critere| {
-                 corresponds || object.get_name().split(" ")
-                     .fold(false, |found, mot_objet| found || basicize(mot_objet).contains(&basicize(mot_critere)))
-             })
-        ).map(|(object_id, _)| object_id).collect()
+        let index = &self.search_index;
+        let (exclus, inclus): (Vec<&str>, Vec<&str>) = search::split_words(critere)
+            .partition(|mot_critere| mot_critere.len() > 1 && mot_critere.starts_with('-'));
+        let found: Option<HashSet<u64>> = inclus.into_iter()
+            .map(|mot_critere| index.matching(&basicize_with(mot_critere, &self.normalize_options)))
+            .reduce(|found, matching| found.intersection(&matching).copied().collect());
+        let rejected: HashSet<u64> = exclus.into_iter()
+            .flat_map(|mot_exclu| index.matching(&basicize_with(&mot_exclu[1..], &self.normalize_options)))
+            .collect();
+        self.database.keys().filter(|object_id|
+            found.as_ref().is_none_or(|found| found.contains(object_id)) && !rejected.contains(object_id)
+        ).collect()
+    }
+
+    /// Recherche des objets d’après leur nom comme [`Bot::search`], en attribuant à chaque résultat
+    /// un score de pertinence. Les résultats sont triés par score décroissant, puis par identifiant.
+    ///
+    /// Le score tient compte du nombre de mots du critère trouvés dans le nom, de la qualité de
+    /// chaque correspondance (mot identique, début de mot ou partie de mot) et de la position du
+    /// mot dans le nom (les premiers mots comptant plus). Un nom identique au critère obtient le
+    /// meilleur score.
+    pub fn search_scored(&self, critere: &str) -> Vec<(u64, f32)> {
+        let mut resultats: Vec<ScoredResult> = self._search_scores(critere).collect();
+        resultats.sort();
+        resultats.into_iter().map(|ScoredResult(id, score)| (id, score)).collect()
+    }
+
+    /// Équivalent de [`Bot::search_scored`] ne renvoyant que les `limit` résultats suivant les
+    /// `offset` premiers, dans le même ordre. Par exemple, `search_paged(critere, 0, 25)` renvoie
+    /// les 25 résultats les plus pertinents.
+    ///
+    /// Seuls les `offset + limit` meilleurs résultats sont conservés pendant le calcul des scores,
+    /// dans un tas borné : ni la mémoire utilisée ni le tri ne dépendent du nombre total de
+    /// résultats, qui peut atteindre des milliers sur une grande base de données alors que seuls
+    /// les premiers sont affichés. Renvoie une liste vide si `offset` dépasse le nombre de
+    /// résultats.
+    pub fn search_paged(&self, critere: &str, offset: usize, limit: usize) -> Vec<(u64, f32)> {
+        let fin = offset.saturating_add(limit);
+        /* Tas des meilleurs résultats, dont le sommet est le moins pertinent */
+        let mut meilleurs = BinaryHeap::new();
+        for resultat in self._search_scores(critere) {
+            if meilleurs.len() < fin {
+                meilleurs.push(resultat);
+            } else if let Some(mut pire) = meilleurs.peek_mut() {
+                if resultat < *pire {
+                    *pire = resultat;
+                }
+            }
+        }
+        meilleurs.into_sorted_vec().into_iter().skip(offset)
+            .map(|ScoredResult(id, score)| (id, score))
+            .collect()
+    }
+
+    /* Résultats de Bot::search associés à leur score de pertinence, dans un ordre quelconque */
+    fn _search_scores(&self, critere: &str) -> impl Iterator<Item = ScoredResult> + '_ {
+        let ids: Vec<u64> = self.search(critere).into_iter().copied().collect();
+        let mots_critere: Vec<String> = search::split_words(critere)
+            .filter(|mot_critere| !mot_critere.starts_with('-'))
+            .map(|mot_critere| basicize_with(mot_critere, &self.normalize_options))
+            .filter(|mot_critere| !mot_critere.is_empty())
+            .collect();
+        ids.into_iter().map(move |id| ScoredResult(id, self.search_index.score(id, &mots_critere)))
+    }
+
+    /// Reconstruit entièrement l’index de recherche utilisé par [`Bot::search`].
+    ///
+    /// L’index est construit au chargement de la base de données par [`Bot::setup`], puis mis à
+    /// jour par les méthodes modifiant la base de données ; cette méthode n’est utile qu’après des
+    /// modifications directes de [`Bot::database`], par exemple un import massif.
+    pub fn rebuild_search_index(&mut self) {
+        self.search_index.rebuild(&self.database, &self.normalize_options);
+    }
+
+    /* Met l’index de recherche à jour pour l’objet donné après son ajout, sa modification ou sa
+       suppression */
+    fn _update_search_index(&mut self, id: u64) {
+        self.search_index.update(id, &self.database, &self.normalize_options);
+    }
+
+    /* Identifiant d’un nouveau multimessage, préfixe des identifiants de ses boutons. Formé du
+       timestamp en millisecondes, incrémenté si nécessaire pour rester strictement supérieur au
+       précédent et ne pas reprendre l’identifiant d’un multimessage en cours. */
+    fn _next_multimessage_id(&mut self) -> Result<String, ErrType> {
+        let maintenant = SystemTime::now().duration_since(SystemTime::UNIX_EPOCH)?.as_millis();
+        let mut numero = maintenant.max(self.last_multimessage_id + 1);
+        while self.multimessages.contains_key(&format!("{}{numero}", tools::MULTIMESSAGE_PREFIX)) {
+            numero += 1;
+        }
+        self.last_multimessage_id = numero;
+        Ok(format!("{}{numero}", tools::MULTIMESSAGE_PREFIX))
     }
 
     /// Envoie les embeds donnés en paramètre au sein d’un seul message à plusieurs pages.
+    ///
+    /// Les boutons de navigation sont grisés après le délai d’expiration des interactions (voir
+    /// [`Bot::interaction_timeout`]). Les pages et la page affichée sont sauvegardées avec la base
+    /// de données (voir [`Bot::save`]) : la navigation reste possible après un redémarrage du bot
+    /// jusqu’à l’expiration, sauf pour les messages éphémères, dont les boutons sont grisés au
+    /// premier clic.
     pub async fn send_embed(&mut self, ctx: &Context<'_, DataType<T>, ErrType>, embeds: Vec<CreateEmbed>) -> Result<(), ErrType> {
-        let id = "mm".to_string() + SystemTime::now().elapsed()?.as_millis().to_string().as_str();
+        let ephemere = self.is_ephemeral_reply(ctx);
         if embeds.len() > 1 {
-            self.multimessages.insert(id.clone(), embeds);
-            self.mmpositions.insert(id.clone(), 0);
-            ctx.send(CreateReply::default()
-                .embed(self.multimessages.get(&id).unwrap().first().unwrap().clone())
-                .components(vec![CreateActionRow::Buttons(vec![
-                    CreateButton::new(id.clone() + "-p")
-                        .label("Précédent")
-                        .disabled(true)
-                        .style(ButtonStyle::Secondary),
-                    CreateButton::new(id.clone() + "-n")
-                        .label("Suivant")
-                        .style(ButtonStyle::Secondary)
-                ])])).await?;
+            let id = self._next_multimessage_id()?;
+            let first = embeds[0].clone();
+            self.multimessages.insert(id.clone(), MultiMessage::new(embeds, ephemere, self.interaction_timeout));
+            let message = _send_multimessage(ctx, id.clone(), first, self.interaction_timeout, ephemere).await?;
+            self._register_multimessage(&id, message);
+            Ok(())
         } else {
-            ctx.send(CreateReply::default().embed(embeds.first()
-                .ok_or(ErrType::EmptyContainer("send_embed appelé avec aucun embed.".to_string()))?.clone())).await?;
+            _send_single_embed(ctx, embeds, ephemere).await
+        }
+    }
+
+    /// Envoie les embeds donnés comme [`Bot::send_embed`], mais sans tenir le verrou sur les
+    /// données du bot pendant l’envoi du message : le verrou n’est pris que brièvement pour
+    /// enregistrer les pages. Les autres commandes et le thread RSS ne sont donc pas bloqués par
+    /// l’appel à Discord.
+    ///
+    /// <div class="warning">
+    /// Le verrou sur les données du bot ne doit pas être tenu lors de l’appel, sous peine
+    /// d’interblocage.
+    /// </div>
+    pub async fn send_embed_unlocked(ctx: &Context<'_, DataType<T>, ErrType>, embeds: Vec<CreateEmbed>) -> Result<(), ErrType> {
+        if embeds.len() > 1 {
+            let first = embeds[0].clone();
+            let (id, timeout, ephemere) = {
+                let mut bot = ctx.data().lock().await;
+                let id = bot._next_multimessage_id()?;
+                let (timeout, ephemere) = (bot.interaction_timeout, bot.is_ephemeral_reply(ctx));
+                bot.multimessages.insert(id.clone(), MultiMessage::new(embeds, ephemere, timeout));
+                (id, timeout, ephemere)
+            };
+            let message = _send_multimessage(ctx, id.clone(), first, timeout, ephemere).await?;
+            ctx.data().lock().await._register_multimessage(&id, message);
+            Ok(())
+        } else {
+            let ephemere = ctx.data().lock().await.is_ephemeral_reply(ctx);
+            _send_single_embed(ctx, embeds, ephemere).await
         }
-        Ok(())
+    }
+
+    /// Renvoie `true` si la réponse à la commande en cours doit être éphémère : c’est le cas des
+    /// commandes de permission [`Permission::READ`] lorsque [`Bot::ephemeral_read_replies`] est
+    /// activé. [`Bot::send_embed`] et [`Bot::send_embed_unlocked`] en tiennent compte ; les
+    /// commandes répondant directement par [`Context::send`] doivent l’appliquer elles-mêmes.
+    pub fn is_ephemeral_reply(&self, ctx: &Context<'_, DataType<T>, ErrType>) -> bool {
+        self.ephemeral_read_replies && ctx.command().custom_data.downcast_ref::<CommandData>()
+            .is_some_and(|data| data.permission == Permission::READ)
     }
 
     /// Appelle [`Affichan::update`] pour tous les affichans, et remet le drapeau
     /// « modifié » des objets à `false` (voir [`Object::set_modified`]).
+    #[instrument(name = "update_affichans", skip_all)]
     pub async fn update_affichans(&mut self, ctx: &SerenityContext) -> Result<(), ErrType> {
+        #[cfg(feature = "metrics")]
+        let debut = std::time::Instant::now();
         try_join_all(self.affichans.iter_mut().map(|affichan| affichan.update(&self.database, ctx))).await?;
         self.database.iter_mut().for_each(|(_, ecrit)| ecrit.set_modified(false));
+        #[cfg(feature = "metrics")]
+        metrics::record_affichans_update(debut.elapsed());
+        Ok(())
+    }
+
+    /// Appelle [`Affichan::update`] uniquement pour les affichans concernés par l’objet donné,
+    /// c’est-à-dire ceux qui le contiennent déjà ou qui devraient le contenir (voir
+    /// [`Affichan::accepts`]), puis remet le drapeau « modifié » de cet objet à `false`.
+    ///
+    /// À utiliser à la place de [`Bot::update_affichans`] après la modification, la création ou la
+    /// suppression d’un seul objet. Les drapeaux « modifié » des autres objets sont conservés : ils
+    /// seront traités au prochain appel à [`Bot::update_affichans`].
+    #[instrument(name = "update_affichan_for", skip(self, ctx))]
+    pub async fn update_affichan_for(&mut self, ctx: &SerenityContext, object_id: u64) -> Result<(), ErrType> {
+        let database = &self.database;
+        try_join_all(self.affichans.iter_mut()
+            .filter(|affichan| affichan.contains_object(&object_id) || affichan.accepts(database, &object_id))
+            .map(|affichan| affichan.update(database, ctx))).await?;
+        if let Some(object) = self.database.get_mut(&object_id) {
+            object.set_modified(false);
+        }
+        Ok(())
+    }
+
+    /// Nettoie le salon d’affichage du salon Discord donné des messages en trop, sans recréer de
+    /// message (voir [`Affichan::clean`]). Renvoie le nombre de messages supprimés, ou [`None`]
+    /// si le salon n’est pas un salon d’affichage.
+    pub async fn clean_affichan(&mut self, chan_id: u64, ctx: &SerenityContext) -> Result<Option<usize>, ErrType> {
+        let self_id = self.self_id.ok_or(ErrType::NoneError)?;
+        let database = &self.database;
+        match self.affichans.iter_mut().find(|affichan| affichan.get_chan_id() == chan_id) {
+            Some(affichan) => Ok(Some(affichan.clean(database, &self_id, ctx).await?)),
+            None => Ok(None)
+        }
+    }
+
+    /// Force la cohérence de l’affichage d’un objet dans tous les salons d’affichage (voir
+    /// [`Affichan::resync_object`]) : présence conforme au test de chaque salon et message à jour,
+    /// indépendamment des drapeaux « modifié ». Remet ensuite le drapeau « modifié » de l’objet à
+    /// `false`.
+    ///
+    /// Utile après des modifications manuelles des salons. Voir aussi la commande `/resync`.
+    #[instrument(name = "resync_object", skip(self, ctx))]
+    pub async fn resync_object(&mut self, object_id: u64, ctx: &SerenityContext) -> Result<(), ErrType> {
+        let database = &self.database;
+        try_join_all(self.affichans.iter_mut()
+            .map(|affichan| affichan.resync_object(database, ctx, object_id))).await?;
+        if let Some(object) = self.database.get_mut(&object_id) {
+            object.set_modified(false);
+        }
         Ok(())
     }
 
@@ -574,6 +2569,19 @@ impl<T: Object> Bot<T> {
         Ok(())
     }
 
+    /* Fournit l’évènement de modification de message aux salons d’affichage pour réécrire le message
+       modifié si c’était un message d’affichage altéré. */
+    async fn check_edits(&mut self, ctx: &SerenityContext, event: &MessageUpdateEvent) -> Result<(), ErrType> {
+        /* Seuls les messages du bot peuvent appartenir à un salon d’affichage */
+        if event.author.as_ref().is_some_and(|author| Some(author.id) != self.self_id) {
+            return Ok(());
+        }
+        let database = &self.database;
+        try_join_all(self.affichans.iter_mut().map(
+            |affichan| affichan.check_message_edit(database, ctx, event))).await?;
+        Ok(())
+    }
+
     /// Copie un template d’embed en y ajoutant le numéro et le contenu des pages.
     #[deprecated(since = "1.1.0", note = "Déplacé à fondabots_lib::tools::get_multimessages")]
     pub fn get_multimessages(pages: Vec<String>, template: CreateEmbed) -> Vec<CreateEmbed> {
@@ -0,0 +1,167 @@
+//! Module de localisation des messages envoyés aux utilisateurs par la bibliothèque.
+//!
+//! Chaque message est identifié par une clé. Les messages peuvent être redéfinis en fournissant
+//! une [`Locale`] au bot par [`crate::Bot::locale`] ; les clés absentes de la [`Locale`] sont
+//! remplacées par le message français par défaut (voir [`default_message`]). Les messages sont
+//! ensuite obtenus par [`crate::Bot::tr`].
+//!
+//! Les messages peuvent contenir des paramètres entre accolades (par exemple `{nom}`), remplacés
+//! lors de l’appel à [`crate::Bot::tr`]. Les paramètres disponibles pour chaque clé sont ceux
+//! présents dans le message français par défaut.
+//!
+//! Les journaux d’audit (voir [`crate::Bot::log`]) ne sont pas concernés et restent en français.
+
+use std::collections::HashMap;
+
+/// Table de traduction associant à une clé de message le message traduit.
+pub type Locale = HashMap<&'static str, String>;
+
+/// Renvoie le message français par défaut associé à la clé donnée, ou [`None`] si la clé
+/// n’existe pas.
+pub fn default_message(key: &str) -> Option<&'static str> {
+    Some(match key {
+        /* Résultats de recherche */
+        "recherche.aucun_resultat" => "Aucun résultat.",
+        "recherche.auteur" => "Recherche : {recherche}",
+        "recherche.titre" => "Résultats de la recherche",
+        "recherche.tous" => "Tous",
+        "recherche.parametre_requis" => "au moins l’un des paramètres doit être spécifié.",
+        "recherche.borne_requise" => "au moins l’une des deux bornes doit être spécifiée.",
+        "recherche_avancee.terme_invalide" => "terme de recherche invalide : {terme}",
+        "recherche_avancee.filtre_inconnu" => "filtre inconnu : {cle}",
+        "recherche.compte.un" => "{nombre} objet trouvé pour {champ} : {valeur}.",
+        "recherche.compte.plusieurs" => "{nombre} objets trouvés pour {champ} : {valeur}.",
+
+        /* Identification d’un objet (voir tools::get_object) */
+        "objet.id_inconnu" => "Aucun objet n’existe avec cet identifiant.",
+        "objet.ambigu" => "Le nom donné référence plus d’un objet. Merci d’affiner le critère ou de rechercher par ID.",
+        "objet.introuvable" => "Aucun objet trouvé.",
+        "objet.lot.question" => "{nombre} objets correspondent à « {critere} ». Continuer ?",
+
+        /* Confirmation (voir tools::confirm) */
+        "confirmation.confirmer" => "Confirmer",
+        "confirmation.annuler" => "Annuler",
+        "confirmation.confirmee" => "Opération confirmée.",
+        "confirmation.annulee" => "Opération annulée.",
+        "confirmation.delai" => "Délai de confirmation dépassé, opération annulée.",
+
+        /* Permissions */
+        "permission.refusee" => "Vous n'avez pas l'autorisation d'utiliser cette commande.",
+        "permission.echec" => "Échec de la vérification de l'autorisation d'utiliser la commande. Réessayez plus tard.",
+
+        /* Interactions (boutons) */
+        "interaction.erreur" => "Une erreur est survenue lors du traitement de cette action.",
+        "modal.ok" => "Objet « {nom} » modifié.",
+
+        /* Commandes de modification de champ */
+        "champ.change" => "{champ} de « {nom} » changé pour « {valeur} »",
+        "champ.remis" => "{champ} de « {nom} » remis à « {valeur} »",
+        "champ.non_effacable" => "le champ {champ} ne peut pas être remis à zéro.",
+        "champ.lot.question" => "Changer {champ} pour « {valeur} » sur les {nombre} objets correspondant à « {critere} » ?",
+        "champ.lot.ok" => "{champ} changé pour « {valeur} » sur {nombre} objets.",
+
+        /* Commandes par défaut */
+        "plop" => "Plop !",
+        "ping.mesure" => "Pong ! Mesure en cours…",
+        "ping.titre" => "Pong !",
+        "ping.gateway" => "Latence du gateway",
+        "ping.aller_retour" => "Aller-retour d’un message",
+        "ping.duree" => "{ms} ms",
+        "ping.inconnue" => "Pas encore mesurée",
+        "aide.titre" => "Commandes disponibles",
+        "aide.sans_categorie" => "Autres",
+        "aide.commande" => "`/{nom}` : {description}",
+        "chercher_id_par_message.inconnu" => "Ce message n’affiche aucun objet d’un salon d’affichage.",
+        "lister_tout.vide" => "La base de données est vide.",
+        "supprimer.ok" => "Objet « {nom} » supprimé.",
+        "supprimer_lot.ok" => "{nombre} objets supprimés.",
+        "annuler.ok" => "Dernière modification annulée !",
+        "annuler.vide" => "Aucune modification récente annulable.",
+        "vider_historique.ok" => "Historique des modifications vidé ({nombre} entrées supprimées).",
+        "annuler_apercu.titre" => "Aperçu de l’annulation",
+        "annuler_apercu.restaure" => "Objet supprimé depuis, il sera restauré :",
+        "annuler_apercu.supprime" => "Objet créé depuis, il sera supprimé.",
+        "annuler_apercu.identique" => "Aucune différence visible.",
+        "update_affichans.ok" => "Affichans mis à jour.",
+        "renommer.ok" => "Objet renommé : « {nom} » → « {nouveau_nom} ». Utilisez /annuler pour revenir en arrière.",
+        "cloner.ok" => "Objet « {nom} » copié sous le nom « {nouveau_nom} » (id : {id}). Utilisez /annuler pour revenir en arrière.",
+        "qui_a_modifie.ok" => "L’objet « {nom} » a été modifié en dernier par {utilisateur}.",
+        "qui_a_modifie.inconnu" => "Aucune modification de l’objet « {nom} » n’a été enregistrée.",
+        "qui_a_modifie.non_supporte" => "Ce bot n’enregistre pas les auteurs des modifications.",
+        "cloner.non_supporte" => "Le clonage n’est pas disponible pour ce bot.",
+        "set_date.ok" => "Date de « {nom} » changée pour le {date}.",
+        "set_date.format" => "la date « {date} » n’est pas au format jj/mm/aaaa.",
+        "set_date.sans_date" => "cet objet n’a pas de date.",
+        "doublons.aucun" => "Aucun doublon trouvé.",
+        "doublons.supprime" => "{nombre} doublon supprimé.",
+        "doublons.supprimes" => "{nombre} doublons supprimés.",
+        "up.ok" => "Objet {nom} up !",
+        "nettoyer_salon.salon_inconnu" => "Ce salon n’est pas un salon d’affichage.",
+        "nettoyer_salon.ok" => "{nombre} messages en trop supprimés du salon {salon}.",
+        "resync.ok" => "Affichage de l’objet « {nom} » resynchronisé.",
+        "epingler.epingle" => "Objet « {nom} » épinglé.",
+        "epingler.desepingle" => "Objet « {nom} » désépinglé.",
+        "epingler.non_supporte" => "L’épinglage n’est pas disponible pour ce bot.",
+        "deplacer.salon_inconnu" => "Ce salon n’est pas un salon d’affichage.",
+        "deplacer.inclus" => "Objet « {nom} » forcé dans le salon {salon}.",
+        "deplacer.exclu" => "Objet « {nom} » forcé hors du salon {salon}.",
+        "deplacer.automatique" => "Objet « {nom} » affiché selon les critères habituels dans le salon {salon}.",
+        "export_affichan.salon_inconnu" => "Ce salon n’est pas un salon d’affichage.",
+        "export_affichan.ok" => "{nombre} objets affichés dans le salon {salon}.",
+        "contenu_salon.salon_inconnu" => "Ce salon n’est pas un salon d’affichage.",
+        "contenu_salon.titre" => "{nombre} objets affichés dans #{salon}",
+        "contenu_salon.vide" => "Le salon {salon} n’affiche aucun objet.",
+        "contenu_salon.en_trop" => "{entree} — *ne devrait pas être affiché*",
+        "contenu_salon.manquant" => "{entree} — *devrait être affiché*",
+        "contenu_salon.inconnu" => "Objet inconnu (id : {id}) — *absent de la base de données*",
+        "orphelins.aucun" => "Tous les objets sont affichés dans au moins un salon d’affichage.",
+        "orphelins.titre" => "Objets affichés dans aucun salon d’affichage",
+        "modifies_en_attente.aucun" => "Aucun objet modifié n’attend la mise à jour des salons d’affichage.",
+        "modifies_en_attente.titre" => "Objets modifiés en attente d’affichage",
+        "refresh_affichans.ok" => "Messages des salons d’affichage réinitialisés.",
+        "reset_affichans.ok" => "Salons d’affichage réinitialisés.",
+        "salon_absolu.ok" => "Salon absolu {nom} défini sur {salon}.",
+        "set_log.ok" => "Salon des logs défini sur {salon}.",
+        "unset_log.ok" => "Salon des logs désactivé.",
+        "bdd.non_supporte" => "l’envoi de la base de données n’est pas disponible pour ce moyen de stockage.",
+        "taille_bdd" => "Il y a actuellement {nombre} écrits dans la base de données.",
+        "stats.titre" => "Statistiques du bot",
+        "stats.objets" => "Objets dans la base de données",
+        "stats.affichans" => "Salons d’affichage",
+        "stats.affichan" => "<#{salon}> : {nombre}",
+        "stats.aucun_affichan" => "Aucun salon d’affichage.",
+        "stats.dernier_rss" => "Dernière mise à jour RSS",
+        "stats.taille_sauvegarde" => "Taille du fichier de sauvegarde",
+        "stats.taille" => "{taille} Kio",
+        "stats.sauvegarde_absente" => "Aucun fichier de sauvegarde.",
+        "stats.taille_indisponible" => "Non disponible pour ce moyen de stockage.",
+        "stats.historique" => "Modifications annulables",
+        "rss_status.titre" => "État du flux RSS",
+        "rss_status.thread" => "Thread RSS",
+        "rss_status.en_cours" => "En cours d’exécution",
+        "rss_status.arrete" => "Arrêté",
+        "rss_status.dernier_succes" => "Dernière mise à jour réussie",
+        "rss_status.jamais" => "Aucune depuis le démarrage.",
+        "rss_status.derniere_erreur" => "Dernière erreur",
+        "rss_status.erreur" => "{date} : {erreur}",
+        "rss_status.aucune_erreur" => "Aucune erreur depuis le démarrage.",
+        "save.ok" => "Base de données sauvegardée !",
+        "maj.ok" => "Mise à jour effectuée !",
+        "reload_commands.ok" => "{nombre} commandes enregistrées auprès de Discord.",
+        "delete_commands.ok" => "Commandes du bot supprimées. Le bot va désormais s’éteindre.",
+        "purger_anciens.aucun" => "Aucun objet de plus de {jours} jours à supprimer.",
+        "purger_anciens.ok" => "Objets de plus de {jours} jours supprimés : {nombre}.",
+        "restaurer_backup.non_supporte" => "la restauration n’est pas disponible pour ce moyen de stockage.",
+        "restaurer_backup.erreur" => "Impossible de charger la sauvegarde de secours : {erreur}",
+        "restaurer_backup.question" => "Remplacer la base de données actuelle par la sauvegarde de secours ({nombre} objets) ?",
+        "restaurer_backup.ok" => "Base de données restaurée !",
+        _ => return None
+    })
+}
+
+/// Remplace les paramètres `{nom}` du message par leur valeur.
+pub fn format_message(message: &str, args: &[(&str, &str)]) -> String {
+    args.iter().fold(message.to_string(), |message, (nom, valeur)| {
+        message.replace(&format!("{{{nom}}}"), valeur)
+    })
+}
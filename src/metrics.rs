@@ -0,0 +1,126 @@
+//! Module optionnel (feature `metrics`) exposant des métriques de supervision du bot au format
+//! texte de Prometheus, sur un petit serveur HTTP répondant à la route `/metrics`.
+//!
+//! Le serveur est démarré par [`crate::Bot::setup`] si un port a été défini avec
+//! [`crate::Bot::metrics_port`], sur l’adresse locale par défaut (voir
+//! [`crate::Bot::metrics_address`]). Les métriques sont mises à jour par la bibliothèque aux endroits
+//! clés (sauvegarde, mise à jour des salons d’affichage, mise à jour RSS) ; elles sont globales
+//! au processus.
+
+use std::net::SocketAddr;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
+
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+use tracing::{error, info};
+
+static DATABASE_SIZE: AtomicU64 = AtomicU64::new(0);
+static AFFICHANS: AtomicU64 = AtomicU64::new(0);
+static SAVES: AtomicU64 = AtomicU64::new(0);
+static SAVE_ERRORS: AtomicU64 = AtomicU64::new(0);
+static RSS_UPDATES: AtomicU64 = AtomicU64::new(0);
+static RSS_ERRORS: AtomicU64 = AtomicU64::new(0);
+static AFFICHANS_UPDATES: AtomicU64 = AtomicU64::new(0);
+/* Durée cumulée des mises à jour des salons d’affichage, en microsecondes */
+static AFFICHANS_UPDATE_MICROS: AtomicU64 = AtomicU64::new(0);
+
+/* Enregistre une sauvegarde de la base de données contenant `taille` objets. */
+pub(crate) fn record_save(taille: usize, succes: bool) {
+    DATABASE_SIZE.store(taille as u64, Ordering::Relaxed);
+    if succes {
+        SAVES.fetch_add(1, Ordering::Relaxed);
+    } else {
+        SAVE_ERRORS.fetch_add(1, Ordering::Relaxed);
+    }
+}
+
+/* Enregistre le nombre de salons d’affichage du bot. */
+pub(crate) fn record_affichans(nombre: usize) {
+    AFFICHANS.store(nombre as u64, Ordering::Relaxed);
+}
+
+/* Enregistre une mise à jour des salons d’affichage et sa durée. */
+pub(crate) fn record_affichans_update(duree: Duration) {
+    AFFICHANS_UPDATES.fetch_add(1, Ordering::Relaxed);
+    AFFICHANS_UPDATE_MICROS.fetch_add(duree.as_micros() as u64, Ordering::Relaxed);
+}
+
+/* Enregistre une mise à jour RSS. */
+pub(crate) fn record_rss_update(succes: bool) {
+    RSS_UPDATES.fetch_add(1, Ordering::Relaxed);
+    if !succes {
+        RSS_ERRORS.fetch_add(1, Ordering::Relaxed);
+    }
+}
+
+/// Renvoie l’ensemble des métriques au format texte de Prometheus.
+pub fn render() -> String {
+    let mut out = String::new();
+    let mut metrique = |nom: &str, type_: &str, aide: &str, valeur: String| {
+        out.push_str(&format!("# HELP {nom} {aide}\n# TYPE {nom} {type_}\n{nom} {valeur}\n"));
+    };
+    metrique("fondabots_database_objects", "gauge",
+             "Nombre d’objets dans la base de données lors de la dernière sauvegarde.",
+             DATABASE_SIZE.load(Ordering::Relaxed).to_string());
+    metrique("fondabots_affichans", "gauge",
+             "Nombre de salons d’affichage.",
+             AFFICHANS.load(Ordering::Relaxed).to_string());
+    metrique("fondabots_saves_total", "counter",
+             "Nombre de sauvegardes réussies de la base de données.",
+             SAVES.load(Ordering::Relaxed).to_string());
+    metrique("fondabots_save_errors_total", "counter",
+             "Nombre de sauvegardes échouées de la base de données.",
+             SAVE_ERRORS.load(Ordering::Relaxed).to_string());
+    metrique("fondabots_rss_updates_total", "counter",
+             "Nombre de mises à jour RSS effectuées.",
+             RSS_UPDATES.load(Ordering::Relaxed).to_string());
+    metrique("fondabots_rss_errors_total", "counter",
+             "Nombre de mises à jour RSS en erreur.",
+             RSS_ERRORS.load(Ordering::Relaxed).to_string());
+    out.push_str("# HELP fondabots_affichans_update_seconds Durée des mises à jour des salons d’affichage.\n");
+    out.push_str("# TYPE fondabots_affichans_update_seconds summary\n");
+    out.push_str(&format!("fondabots_affichans_update_seconds_sum {}\n",
+                          AFFICHANS_UPDATE_MICROS.load(Ordering::Relaxed) as f64 / 1_000_000.0));
+    out.push_str(&format!("fondabots_affichans_update_seconds_count {}\n",
+                          AFFICHANS_UPDATES.load(Ordering::Relaxed)));
+    out
+}
+
+/* Répond à une requête HTTP : les métriques pour GET /metrics, une 404 sinon. */
+async fn _handle_connection(mut stream: TcpStream) -> std::io::Result<()> {
+    let mut buffer = [0; 1024];
+    let lu = stream.read(&mut buffer).await?;
+    let requete = String::from_utf8_lossy(&buffer[..lu]);
+    let reponse = if requete.starts_with("GET /metrics ") {
+        let corps = render();
+        format!("HTTP/1.1 200 OK\r\nContent-Type: text/plain; version=0.0.4; charset=utf-8\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{corps}",
+                corps.len())
+    } else {
+        "HTTP/1.1 404 Not Found\r\nContent-Length: 0\r\nConnection: close\r\n\r\n".to_string()
+    };
+    stream.write_all(reponse.as_bytes()).await?;
+    stream.shutdown().await
+}
+
+/* Démarre le serveur de métriques sur l’adresse donnée, dans une tâche tokio. Appelé par
+   Bot::setup. */
+pub(crate) async fn serve(adresse: SocketAddr) -> std::io::Result<()> {
+    let listener = TcpListener::bind(adresse).await?;
+    info!("Métriques disponibles sur {adresse}.");
+    tokio::spawn(async move {
+        loop {
+            match listener.accept().await {
+                Ok((stream, _)) => {
+                    tokio::spawn(async move {
+                        if let Err(e) = _handle_connection(stream).await {
+                            error!("Erreur du serveur de métriques : {e}");
+                        }
+                    });
+                }
+                Err(e) => error!("Erreur du serveur de métriques : {e}")
+            }
+        }
+    });
+    Ok(())
+}
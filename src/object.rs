@@ -6,15 +6,17 @@
 //!
 //! [`Object`] contient également des fonctions et paramètres généraux du bot qui doivent être modifiés
 //! pour son bon fonctionnement.
+use std::collections::HashMap;
 use std::fmt::{Debug, Display};
 use std::str::FromStr;
 
+use chrono::{DateTime, Utc};
 use poise::CreateReply;
 use poise::{serenity_prelude as serenity, ChoiceParameter};
 use serenity::all::ArgumentConvert;
 use serenity::Context as SerenityContext;
-use serenity::{ComponentInteraction, CreateActionRow, CreateEmbed, CreateMessage, EditMessage, Timestamp};
-use yaml_rust2::Yaml;
+use serenity::{ComponentInteraction, CreateActionRow, CreateAttachment, CreateEmbed, CreateMessage, CreateModal, EditAttachments, EditMessage, Reaction, ReactionType, Timestamp, UserId};
+use yaml_rust2::{yaml, Yaml};
 
 use crate::{Bot, DataType, ErrType};
 
@@ -74,6 +76,13 @@ pub trait Object: Send + Sync + 'static + PartialEq + Clone + Debug {
     /// dans la base de données de [`Bot`] sous peine de causer des comportements imprévisibles.
     fn get_id(&self) -> u64;
 
+    /// Modifie l’identifiant de l’objet. Utilisée par la commande `/cloner` pour attribuer un
+    /// nouvel identifiant à la copie d’un objet.
+    ///
+    /// Par défaut, ne fait rien : la commande `/cloner` signale alors que le clonage n’est pas
+    /// disponible.
+    fn set_id(&mut self, _id: u64) {}
+
     /// Renvoie un nouvel [`Object`] d’après des données au format [`Yaml`]. La structure de ce
     /// format est laissée libre, mais doit être cohérente avec [`Object::serialize`].
     fn from_yaml(data: &Yaml) -> Result<Self, ErrType>;
@@ -90,44 +99,93 @@ pub trait Object: Send + Sync + 'static + PartialEq + Clone + Debug {
     /// une fois la mise à jour faite dans les [`crate::affichan::Affichan`].
     fn set_modified(&mut self, modified: bool);
 
+    /// Méthode appelée après chaque modification de l’objet par la bibliothèque, via
+    /// [`crate::Bot::mark_modified`]. Elle permet par exemple de recalculer un champ dérivé
+    /// ou de mettre à jour une date de dernière modification.
+    ///
+    /// Elle est appelée après [`Object::set_modified`] et avant la mise à jour des
+    /// [`crate::affichan::Affichan`] : les changements effectués ici sont donc pris en compte
+    /// dans l’affichage. Par défaut, elle ne fait rien.
+    fn on_modified(&mut self) {}
+
     /// Renvoie l’embed correspondant à l’objet.
     ///
     /// <div class="warning">
     /// Pour le bon fonctionnement de l’initialisation des Affichan d’après les messages
     /// qui y sont déjà, l’identifiant de l’objet (voir Object::get_id) doit impérativement
-    /// se trouver dans le footer de l’embed.
+    /// se trouver dans le footer de l’embed, sous une forme lue par [`Object::parse_footer_id`].
     /// </div>
+    ///
+    /// Il est recommandé de partir de l’embed renvoyé par [`crate::tools::object_embed`], qui
+    /// contient déjà l’identifiant dans le footer, et d’y ajouter les champs de l’objet.
     fn get_embed(&self) -> CreateEmbed;
 
+    /// Extrait l’identifiant de l’objet du texte du footer de l’embed de son message (voir
+    /// [`Object::get_embed`]), ou renvoie [`None`] si le footer ne contient pas d’identifiant.
+    ///
+    /// Par défaut, le footer doit contenir uniquement l’identifiant. À redéfinir si le footer
+    /// contient d’autres informations (par exemple « ID : 42 »), sans quoi les messages des salons
+    /// d’affichage ne sont pas reconnus lors de leur chargement et sont supprimés.
+    fn parse_footer_id(footer: &str) -> Option<u64> {
+        footer.trim().parse().ok()
+    }
+
     /// Renvoie les boutons qui apparaissent sous les messages individuels des objets.
     /// Il est possible de n’en inclure aucun en laissant l’action row vide.
     ///
     /// Chaque bouton doit avoir un traitement défini dans [`Object::buttons`].
     ///
     /// <div class="warning">
-    /// Les identifiants de boutons commençant par "mm-" sont réservés pour le traitement des
-    /// messages à plusieurs pages. Utiliser un tel identifiant ailleurs causera un mauvais traitement
-    /// du bouton et des résultats imprévisibles (mais certainement pas ceux voulus, car Object::buttons
-    /// ne sera pas appelé).
+    /// Les identifiants de boutons commençant par [`crate::tools::MULTIMESSAGE_PREFIX`] sont réservés
+    /// pour le traitement des messages à plusieurs pages, ainsi que ceux des anciens messages à
+    /// plusieurs pages (`mm` suivi de chiffres et de `-p` ou `-n`). Utiliser un tel identifiant
+    /// ailleurs causera un mauvais traitement du bouton et des résultats imprévisibles (mais
+    /// certainement pas ceux voulus, car Object::buttons ne sera pas appelé).
+    /// Il en va de même pour les identifiants commençant par [`crate::tools::CONFIRM_PREFIX`],
+    /// réservés aux boutons de confirmation, et par [`crate::tools::MODAL_PREFIX`], réservés aux
+    /// boutons d’édition par formulaire. Les identifiants réservés utilisés par les objets sont
+    /// signalés dans les logs au démarrage (voir [`crate::tools::is_reserved_id`]).
     /// </div>
     fn get_buttons(&self) -> CreateActionRow;
 
-    /// Renvoie un [`CreateMessage`] créant un message contenant l’embed de [`Object::get_embed`]
-    /// et les boutons de [`Object::get_buttons`].
+    /// Renvoie les fichiers joints aux messages de l’objet, par exemple des images stockées
+    /// localement. Par défaut, aucun fichier n’est joint.
+    ///
+    /// Un fichier joint peut être affiché dans l’embed de [`Object::get_embed`] en y référençant
+    /// l’URL `attachment://` suivie du nom du fichier ([`CreateAttachment::filename`]), par exemple
+    /// avec [`CreateEmbed::thumbnail`] ; voir [`crate::tools::attachment_url`].
+    fn get_attachments(&self) -> Vec<CreateAttachment> {
+        Vec::new()
+    }
+
+    /// Renvoie un [`CreateMessage`] créant un message contenant l’embed de [`Object::get_embed`],
+    /// les boutons de [`Object::get_buttons`] et les fichiers de [`Object::get_attachments`].
     fn get_message(&self) -> CreateMessage {
         CreateMessage::new().embed(self.get_embed()).components(vec![self.get_buttons()])
+            .add_files(self.get_attachments())
     }
 
     /// Renvoie un [`EditMessage`] remplaçant un message par un autre contenant l’embed de
     /// [`Object::get_embed`] et les boutons de [`Object::get_buttons`].
+    ///
+    /// Si [`Object::get_attachments`] renvoie des fichiers, ils remplacent les fichiers joints
+    /// au message. Sinon, les fichiers joints au message sont conservés.
     fn get_message_edit(&self) -> EditMessage {
-        EditMessage::new().embed(self.get_embed()).components(vec![self.get_buttons()])
+        let edit = EditMessage::new().embed(self.get_embed()).components(vec![self.get_buttons()]);
+        let attachments = self.get_attachments();
+        if attachments.is_empty() {
+            edit
+        } else {
+            edit.attachments(attachments.into_iter().fold(EditAttachments::new(), EditAttachments::add))
+        }
     }
 
-    /// Renvoie un [`CreateReply`] créant une réponse contenant l’embed de [`Object::get_embed`]
-    /// et les boutons de [`Object::get_buttons`].
+    /// Renvoie un [`CreateReply`] créant une réponse contenant l’embed de [`Object::get_embed`],
+    /// les boutons de [`Object::get_buttons`] et les fichiers de [`Object::get_attachments`].
     fn get_reply(&self) -> CreateReply {
-        CreateReply::default().embed(self.get_embed()).components(vec![self.get_buttons()])
+        self.get_attachments().into_iter().fold(
+            CreateReply::default().embed(self.get_embed()).components(vec![self.get_buttons()]),
+            CreateReply::attachment)
     }
 
     /// Renvoie le nom de l’objet.
@@ -140,6 +198,33 @@ pub trait Object: Send + Sync + 'static + PartialEq + Clone + Debug {
     /// de résultats.
     fn get_list_entry(&self) -> String;
 
+    /// Renvoie l’URL de la page web correspondant à l’objet, s’il en a une. Par défaut, renvoie
+    /// [`None`].
+    ///
+    /// Si elle est définie, le nom de l’objet est rendu cliquable dans les listes de résultats
+    /// affichées par la bibliothèque (voir [`crate::tools::list_entry`]) : il n’est alors pas
+    /// nécessaire de formater le lien dans [`Object::get_list_entry`].
+    fn get_url(&self) -> Option<String> {
+        None
+    }
+
+    /// Renvoie une représentation lisible des champs de l’objet, une ligne par champ, utilisée
+    /// notamment par la commande `/annuler_apercu` pour afficher les différences entre deux états
+    /// d’un objet. Par défaut, renvoie [`Object::get_list_entry`].
+    fn summary(&self) -> String {
+        self.get_list_entry()
+    }
+
+    /// Renvoie les différences entre cet objet et un autre état du même objet, sous la forme
+    /// (champ, valeur de cet objet, valeur de l’autre objet), pour les champs qui diffèrent.
+    ///
+    /// Utilisée pour détailler les logs des commandes d’édition (ancienne et nouvelle valeur) et
+    /// l’aperçu de la commande `/annuler_apercu`. Par défaut, renvoie un vecteur vide : les logs
+    /// restent alors succincts et l’aperçu compare les résumés ([`Object::summary`]).
+    fn diff(&self, _other: &Self) -> Vec<(String, String, String)> {
+        Vec::new()
+    }
+
     /// Méthode appelée dans la commande par défaut `/up` qui supprime l’objet des [`crate::affichan::Affichan`] pour
     /// republier le message correspondant en tant que message le plus récent des salons.
     ///
@@ -148,22 +233,90 @@ pub trait Object: Send + Sync + 'static + PartialEq + Clone + Debug {
 
     /// Fonction traitant les boutons définis dans [`Object::get_buttons`].
     ///
+    /// Si une erreur est renvoyée, la bibliothèque signale l’erreur à l’utilisateur par un message
+    /// éphémère, envoyé en réponse à l’interaction ou, si une réponse a déjà été envoyée, en suivi
+    /// de celle-ci.
+    ///
     /// <div class="warning">
-    /// Les identifiants de boutons commençant par "mm-" sont réservés pour le traitement des
-    /// messages à plusieurs pages. Utiliser un tel identifiant ailleurs causera un mauvais traitement
-    /// du bouton et des résultats imprévisibles (mais certainement pas ceux voulus, car Object::buttons
-    /// ne sera pas appelé).
+    /// Les identifiants de boutons commençant par [`crate::tools::MULTIMESSAGE_PREFIX`] sont réservés
+    /// pour le traitement des messages à plusieurs pages, ainsi que ceux des anciens messages à
+    /// plusieurs pages (`mm` suivi de chiffres et de `-p` ou `-n`). Utiliser un tel identifiant
+    /// ailleurs causera un mauvais traitement du bouton et des résultats imprévisibles (mais
+    /// certainement pas ceux voulus, car Object::buttons ne sera pas appelé).
+    /// Il en va de même pour les identifiants commençant par [`crate::tools::CONFIRM_PREFIX`],
+    /// réservés aux boutons de confirmation, et par [`crate::tools::MODAL_PREFIX`], réservés aux
+    /// boutons d’édition par formulaire. Les identifiants réservés utilisés par les objets sont
+    /// signalés dans les logs au démarrage (voir [`crate::tools::is_reserved_id`]).
     /// </div>
     fn buttons(ctx: &SerenityContext, interaction: &mut ComponentInteraction, bot: &mut Bot<Self>) -> impl std::future::Future<Output = Result<(), ErrType>> + Send;
 
+    /// Renvoie le formulaire Discord d’édition de l’objet, pré-rempli avec ses champs, ouvert par
+    /// un bouton créé par [`crate::tools::modal_button`]. Le formulaire doit être créé avec
+    /// l’identifiant `custom_id` donné, qui permet à la bibliothèque de retrouver l’objet.
+    ///
+    /// Par défaut, l’objet n’a pas de formulaire : le bouton signale alors une erreur.
+    fn modal(&self, _custom_id: String) -> Option<CreateModal> {
+        None
+    }
+
+    /// Applique à l’objet les valeurs d’un formulaire de [`Object::modal`] rempli par un
+    /// utilisateur. Les valeurs sont indexées par l’identifiant (`custom_id`) des champs du
+    /// formulaire ; les champs laissés vides ont pour valeur une chaîne vide.
+    ///
+    /// La modification est archivée (annulable) et les salons d’affichage sont mis à jour. En
+    /// cas d’erreur, l’objet n’est pas modifié et l’erreur est signalée à l’utilisateur ; le
+    /// message d’une [`ErrType::CommandUseError`] lui est affiché tel quel.
+    fn apply_modal(&mut self, _values: &HashMap<String, String>) -> Result<(), ErrType> {
+        Ok(())
+    }
+
+    /// Renvoie les réactions ajoutées par le bot sous les messages des objets dans les salons
+    /// d’affichage, proposant des actions en alternative aux boutons. Par défaut, aucune.
+    ///
+    /// Les réactions sont ajoutées à chaque publication du message dans un salon d’affichage,
+    /// y compris lorsqu’un message supprimé est republié ; elles ne sont pas réappliquées lors
+    /// de la modification d’un message existant.
+    fn get_reactions(&self) -> Vec<ReactionType> {
+        Vec::new()
+    }
+
+    /// Fonction traitant les réactions ajoutées par les utilisateurs sous les messages d’objets,
+    /// notamment celles de [`Object::get_reactions`]. L’objet est retrouvé d’après l’identifiant
+    /// contenu dans le footer de l’embed du message (voir [`Object::get_embed`]) et est fourni
+    /// par son identifiant. Les réactions ajoutées par le bot lui-même ne sont pas transmises.
+    ///
+    /// Par défaut, ne fait rien. La réception des réactions nécessite l’intent
+    /// `GUILD_MESSAGE_REACTIONS` (voir [`Bot::setup`]). Les salons d’affichage sont mis à jour
+    /// après le traitement de la réaction.
+    fn reactions(_ctx: &SerenityContext, _reaction: &Reaction, _object_id: u64, _bot: &mut Bot<Self>) -> impl std::future::Future<Output = Result<(), ErrType>> + Send {
+        async { Ok(()) }
+    }
+
     /// Fonction traitant les mises à jour de la base de données d’après un flux CSS.
     fn maj_rss(bot: &DataType<Self>) -> impl std::future::Future<Output = Result<(), ErrType>> + Send;
 
+    /// Indique si l’objet possède une date. Par défaut, tous les objets en ont une, pour ne pas
+    /// changer le comportement des bots existants, qui implémentent [`Object::get_date`].
+    ///
+    /// <div class="warning">
+    /// Avec l’implémentation par défaut, [`Object::get_date`] doit être réimplémentée, sous peine
+    /// de panique lors des tris, de la recherche avancée ou de la purge des anciens objets.
+    /// </div>
+    ///
+    /// Les bots dont les objets n’ont pas de notion de date doivent donc réimplémenter cette
+    /// méthode pour renvoyer `false` : la bibliothèque n’appellera alors plus
+    /// [`Object::get_date`] ni [`Object::set_date`] pour ces objets, qui sont placés en fin de liste
+    /// lors des tris (voir [`crate::tools::sort_by_date`]).
+    fn has_date(&self) -> bool { /* TODO 2.0 remplacer par un get_date renvoyant une Option */
+        true
+    }
+
     /// Renvoie la date de l’objet.
     ///
     /// <div class="warning">
     /// Bien que ça ne soit pas requis pour la compilation pour des raisons de compatiblité rétroactive,
-    /// tout appel à cette méthode non-réimplémentée aboutira en une panique.
+    /// tout appel à cette méthode non-réimplémentée aboutira en une panique, sauf si
+    /// [`Object::has_date`] renvoie `false`.
     /// </div>
     fn get_date(&self) -> &Timestamp { /* TODO 2.0 rendre obligatoire */
         unimplemented!("Cette méthode devrait être ré-implémentée.")
@@ -178,6 +331,73 @@ pub trait Object: Send + Sync + 'static + PartialEq + Clone + Debug {
     fn set_date(&mut self, _t: Timestamp) { /* TODO 2.0 rendre obligatoire */
         unimplemented!("Cette méthode devrait être ré-implémentée.")
     }
+
+    /// Renvoie la priorité de l’objet, utilisée comme clé de tri principale avant la date (voir
+    /// [`crate::tools::sort_by_date`]) : les objets de plus haute priorité sont placés en tête des
+    /// listes et publiés en dernier dans les salons d’affichage, donc au plus près du bas du salon.
+    /// Les objets de même priorité sont triés par date, du plus récent au plus ancien.
+    ///
+    /// Par défaut, tous les objets ont la priorité `0` : le tri se fait alors uniquement par date.
+    ///
+    /// Dans les salons d’affichage, seuls les objets ajoutés lors d’une même mise à jour sont triés
+    /// entre eux ; un changement de priorité ne déplace pas un message déjà publié (voir la
+    /// commande `/up`).
+    fn get_priority(&self) -> i64 {
+        0
+    }
+
+    /// Indique si l’objet est épinglé. Les objets épinglés sont placés avant tous les autres,
+    /// quelle que soit leur priorité ou leur date (voir [`crate::tools::sort_by_date`]) : dans les
+    /// salons d’affichage, ils restent au plus près du bas du salon, les nouveaux objets étant
+    /// publiés avant eux.
+    ///
+    /// Par défaut, aucun objet n’est épinglé.
+    fn is_pinned(&self) -> bool {
+        false
+    }
+
+    /// Épingle ou désépingle l’objet (voir [`Object::is_pinned`]). Utilisée par la commande
+    /// `/epingler` ; l’état doit être conservé par [`Object::serialize`] pour persister.
+    ///
+    /// Par défaut, ne fait rien : la commande `/epingler` signale alors que l’épinglage n’est pas
+    /// disponible.
+    fn set_pinned(&mut self, _pinned: bool) {}
+
+    /// Vérifie que l’objet correspond au filtre `cle:valeur` d’une recherche avancée (voir
+    /// [`crate::tools::parse_advanced_query`]). Renvoie [`None`] si la clé n’est pas reconnue.
+    ///
+    /// Par défaut, aucune clé n’est reconnue. Pour les propriétés [`Field`], utiliser
+    /// [`crate::tools::field_matches`], par exemple :
+    /// `match cle { "etat" => tools::field_matches::<Self, Etat>(self, valeur), _ => None }`.
+    fn matches_filter(&self, _cle: &str, _valeur: &str) -> Option<bool> {
+        None
+    }
+
+    /// Renvoie les dates de création et de dernière modification de l’objet, si l’objet les
+    /// gère (voir [`Timestamped`]). Par défaut, renvoie [`None`].
+    fn as_timestamped(&self) -> Option<&dyn Timestamped> {
+        None
+    }
+
+    /// Version mutable de [`Object::as_timestamped`], utilisée par [`Bot::insert`] et
+    /// [`Bot::mark_modified`] pour mettre à jour les dates. Par défaut, renvoie [`None`] : les
+    /// dates ne sont alors pas gérées.
+    fn as_timestamped_mut(&mut self) -> Option<&mut dyn Timestamped> {
+        None
+    }
+
+    /// Renvoie le dernier utilisateur ayant modifié l’objet, si l’objet le gère (voir
+    /// [`Editable`]). Par défaut, renvoie [`None`].
+    fn as_editable(&self) -> Option<&dyn Editable> {
+        None
+    }
+
+    /// Version mutable de [`Object::as_editable`], utilisée par [`Bot::mark_modified_by`] pour
+    /// enregistrer le dernier utilisateur ayant modifié l’objet. Par défaut, renvoie [`None`] :
+    /// cet utilisateur n’est alors pas enregistré.
+    fn as_editable_mut(&mut self) -> Option<&mut dyn Editable> {
+        None
+    }
 }
 
 /// Ce trait permet d’utiliser les fonctions auxiliaires génériques de [`crate::generic_commands`] sur des
@@ -197,4 +417,151 @@ pub trait Field<T: Object>: Eq + ChoiceParameter + Display + Clone + Sync + Argu
     /// En général, cela correspond simplement au nom de la structure implémentant [`Field`], ou à
     /// une version plus naturelle de celle-ci (avec espaces et accents par exemple).
     fn field_name() -> &'static str;
-}
\ No newline at end of file
+
+    /// Renvoie la valeur neutre du champ, utilisée par [`crate::generic_commands::clear_field`]
+    /// pour remettre ce champ à zéro (par exemple, un état « Nouveau »).
+    ///
+    /// Par défaut, renvoie [`None`] : le champ n’a pas de valeur neutre et
+    /// [`crate::generic_commands::clear_field`] signale alors qu’il ne peut pas être remis à zéro.
+    fn default_value() -> Option<Self> {
+        None
+    }
+}
+
+/// Ce trait complète [`Field`] pour les propriétés numériques d’un [`Object`] (note, nombre de
+/// votes…), permettant de filtrer les objets selon un intervalle plutôt que par égalité. Il est
+/// utilisé par [`crate::generic_commands::lister_range`].
+pub trait RangeField<T: Object> {
+    /// Vérifie que la propriété de l’objet est comprise dans l’intervalle donné, bornes incluses.
+    /// Une borne [`None`] signifie que l’intervalle n’est pas borné de ce côté.
+    fn in_range(obj: &T, min: Option<i64>, max: Option<i64>) -> bool;
+
+    /// Renvoie une chaîne de caractères statiques correspondant au nom du paramètre (voir
+    /// [`Field::field_name`]).
+    fn field_name() -> &'static str;
+}
+
+/// Ce trait complète [`Object`] pour les objets dont la bibliothèque gère automatiquement les
+/// dates de création et de dernière modification : la date de création est définie par
+/// [`Bot::insert`] à l’ajout de l’objet, et la date de modification par [`Bot::insert`] et
+/// [`Bot::mark_modified`].
+///
+/// Pour l’activer, implémenter [`Object::as_timestamped`] et [`Object::as_timestamped_mut`] en
+/// renvoyant `Some(self)`, ou une référence vers un champ [`Timestamps`] de l’objet. La
+/// persistance des dates est à la charge de [`Object::serialize`] et [`Object::from_yaml`]
+/// (voir [`Timestamps::write_yaml`] et [`Timestamps::from_yaml`]).
+pub trait Timestamped {
+    /// Renvoie la date de création de l’objet, si elle est connue.
+    fn created_at(&self) -> Option<DateTime<Utc>>;
+
+    /// Renvoie la date de dernière modification de l’objet, si elle est connue.
+    fn updated_at(&self) -> Option<DateTime<Utc>>;
+
+    /// Définit la date de création de l’objet.
+    fn set_created_at(&mut self, date: DateTime<Utc>);
+
+    /// Définit la date de dernière modification de l’objet.
+    fn set_updated_at(&mut self, date: DateTime<Utc>);
+}
+
+/// Implémentation prête à l’emploi de [`Timestamped`], à inclure comme champ d’un [`Object`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Timestamps {
+    /// Date de création de l’objet.
+    pub created_at: Option<DateTime<Utc>>,
+    /// Date de dernière modification de l’objet.
+    pub updated_at: Option<DateTime<Utc>>
+}
+
+impl Timestamps {
+    /// Lit les dates des clés `created_at` et `updated_at` (timestamps Unix) de l’entrée YAML
+    /// d’un objet. Les dates absentes ou invalides sont laissées à [`None`], ce qui permet de
+    /// charger les sauvegardes antérieures.
+    pub fn from_yaml(yaml: &Yaml) -> Self {
+        let date = |key: &str| yaml[key].as_i64().and_then(|timestamp| DateTime::from_timestamp(timestamp, 0));
+        Self {
+            created_at: date("created_at"),
+            updated_at: date("updated_at")
+        }
+    }
+
+    /// Écrit les dates connues dans l’entrée YAML d’un objet, sous les clés `created_at` et
+    /// `updated_at` (timestamps Unix).
+    pub fn write_yaml(&self, hash: &mut yaml::Hash) {
+        for (key, date) in [("created_at", self.created_at), ("updated_at", self.updated_at)] {
+            if let Some(date) = date {
+                hash.insert(Yaml::String(key.into()), Yaml::Integer(date.timestamp()));
+            }
+        }
+    }
+}
+
+impl Timestamped for Timestamps {
+    fn created_at(&self) -> Option<DateTime<Utc>> {
+        self.created_at
+    }
+
+    fn updated_at(&self) -> Option<DateTime<Utc>> {
+        self.updated_at
+    }
+
+    fn set_created_at(&mut self, date: DateTime<Utc>) {
+        self.created_at = Some(date);
+    }
+
+    fn set_updated_at(&mut self, date: DateTime<Utc>) {
+        self.updated_at = Some(date);
+    }
+}
+
+/// Ce trait complète [`Object`] pour les objets dont la bibliothèque enregistre le dernier
+/// utilisateur les ayant modifiés, défini par [`Bot::mark_modified_by`] lors des modifications
+/// faites par les commandes d’édition. Il est consultable par la commande `/qui_a_modifie`.
+///
+/// Pour l’activer, implémenter [`Object::as_editable`] et [`Object::as_editable_mut`] en
+/// renvoyant `Some(self)`, ou une référence vers un champ [`EditInfo`] de l’objet. La persistance
+/// est à la charge de [`Object::serialize`] et [`Object::from_yaml`] (voir
+/// [`EditInfo::write_yaml`] et [`EditInfo::from_yaml`]).
+pub trait Editable {
+    /// Renvoie le dernier utilisateur ayant modifié l’objet, s’il est connu.
+    fn last_editor(&self) -> Option<UserId>;
+
+    /// Définit le dernier utilisateur ayant modifié l’objet.
+    fn set_last_editor(&mut self, user: UserId);
+}
+
+/// Implémentation prête à l’emploi de [`Editable`], à inclure comme champ d’un [`Object`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct EditInfo {
+    /// Dernier utilisateur ayant modifié l’objet.
+    pub last_editor: Option<UserId>
+}
+
+impl EditInfo {
+    /// Lit l’utilisateur de la clé `last_editor` (identifiant Discord) de l’entrée YAML d’un
+    /// objet. Un utilisateur absent ou invalide est laissé à [`None`], ce qui permet de charger les
+    /// sauvegardes antérieures.
+    pub fn from_yaml(yaml: &Yaml) -> Self {
+        Self {
+            last_editor: yaml["last_editor"].as_i64().filter(|&id| id > 0).map(|id| UserId::new(id as u64))
+        }
+    }
+
+    /// Écrit l’utilisateur, s’il est connu, dans l’entrée YAML d’un objet sous la clé
+    /// `last_editor` (identifiant Discord).
+    pub fn write_yaml(&self, hash: &mut yaml::Hash) {
+        if let Some(user) = self.last_editor {
+            hash.insert(Yaml::String("last_editor".into()), Yaml::Integer(user.get() as i64));
+        }
+    }
+}
+
+impl Editable for EditInfo {
+    fn last_editor(&self) -> Option<UserId> {
+        self.last_editor
+    }
+
+    fn set_last_editor(&mut self, user: UserId) {
+        self.last_editor = Some(user);
+    }
+}
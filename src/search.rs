@@ -0,0 +1,133 @@
+//! Module contenant l’index de recherche utilisé par [`crate::Bot::search`].
+
+use std::cmp::Ordering;
+use std::collections::{HashMap, HashSet};
+
+use crate::object::Object;
+use crate::tools::{basicize_with, NormalizeOptions};
+
+/// Index inversé des mots normalisés des noms d’objets.
+///
+/// L’index est tenu à jour par les méthodes de [`crate::Bot`] modifiant la base de données, qui
+/// (ré)indexent les seuls objets concernés : une recherche n’a donc pas à parcourir ni normaliser
+/// toute la base. Il est reconstruit entièrement par [`SearchIndex::rebuild`] au chargement de la
+/// base de données.
+#[derive(Default)]
+pub(crate) struct SearchIndex {
+    /* Mots normalisés du nom de chaque objet */
+    words: HashMap<u64, Vec<String>>,
+    /* Index inversé : mot normalisé -> objets dont le nom contient ce mot */
+    index: HashMap<String, HashSet<u64>>
+}
+
+impl SearchIndex {
+    /// Indexe un objet, en remplaçant son éventuelle entrée précédente.
+    pub(crate) fn insert(&mut self, id: u64, name: &str, options: &NormalizeOptions) {
+        self.remove(id);
+        let words: Vec<String> = split_words(name).map(|mot| basicize_with(mot, options)).collect();
+        words.iter().for_each(|mot| {
+            self.index.entry(mot.clone()).or_default().insert(id);
+        });
+        self.words.insert(id, words);
+    }
+
+    /// Retire un objet de l’index.
+    pub(crate) fn remove(&mut self, id: u64) {
+        if let Some(words) = self.words.remove(&id) {
+            words.iter().for_each(|mot| {
+                if let Some(ids) = self.index.get_mut(mot) {
+                    ids.remove(&id);
+                    if ids.is_empty() {
+                        self.index.remove(mot);
+                    }
+                }
+            });
+        }
+    }
+
+    /// Met l’index à jour pour l’objet d’identifiant donné : l’objet est réindexé s’il est dans la
+    /// base de données, et retiré de l’index sinon.
+    pub(crate) fn update<T: Object>(&mut self, id: u64, database: &HashMap<u64, T>, options: &NormalizeOptions) {
+        match database.get(&id) {
+            Some(object) => self.insert(id, object.get_name(), options),
+            None => self.remove(id)
+        }
+    }
+
+    /// Reconstruit entièrement l’index d’après la base de données.
+    pub(crate) fn rebuild<T: Object>(&mut self, database: &HashMap<u64, T>, options: &NormalizeOptions) {
+        self.words.clear();
+        self.index.clear();
+        database.iter().for_each(|(&id, object)| self.insert(id, object.get_name(), options));
+    }
+
+    /// Calcule le score de pertinence d’un objet indexé pour les mots du critère donnés, déjà
+    /// normalisés. Chaque mot du critère rapporte des points selon son meilleur correspondant dans
+    /// le nom : 1 s’il lui est égal, 0,75 s’il le commence, 0,5 s’il y est contenu, plus un bonus
+    /// de position (0,25 pour le premier mot du nom, décroissant ensuite). Un nom égal au critère
+    /// rapporte un point supplémentaire. Renvoie 0 si aucun mot ne correspond.
+    pub(crate) fn score(&self, id: u64, mots_critere: &[String]) -> f32 {
+        let Some(words) = self.words.get(&id) else {
+            return 0.0;
+        };
+        let score: f32 = mots_critere.iter().map(|mot_critere| {
+            words.iter().enumerate().filter_map(|(position, mot)| {
+                let base = if mot == mot_critere {
+                    1.0
+                } else if mot.starts_with(mot_critere.as_str()) {
+                    0.75
+                } else if mot.contains(mot_critere.as_str()) {
+                    0.5
+                } else {
+                    return None;
+                };
+                Some(base + 0.25 / (position + 1) as f32)
+            }).fold(0.0, f32::max)
+        }).sum();
+        if score > 0.0 && words == mots_critere {
+            score + 1.0
+        } else {
+            score
+        }
+    }
+
+    /// Renvoie les objets dont l’un des mots du nom contient le mot donné, déjà normalisé.
+    pub(crate) fn matching(&self, mot_critere: &str) -> HashSet<u64> {
+        self.index.iter()
+            .filter(|(mot, _)| mot.contains(mot_critere))
+            .flat_map(|(_, ids)| ids.iter().copied())
+            .collect()
+    }
+}
+
+/// Résultat de recherche associé à son score de pertinence. L’ordre est celui des résultats de
+/// [`crate::Bot::search_scored`] : score décroissant, puis identifiant croissant, le résultat le
+/// plus pertinent étant donc le plus petit.
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct ScoredResult(pub(crate) u64, pub(crate) f32);
+
+impl Ord for ScoredResult {
+    fn cmp(&self, other: &Self) -> Ordering {
+        other.1.total_cmp(&self.1).then_with(|| self.0.cmp(&other.0))
+    }
+}
+
+impl PartialOrd for ScoredResult {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl PartialEq for ScoredResult {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for ScoredResult {}
+
+/// Découpe un nom ou un critère de recherche en mots, séparés par tout type d’espace (y compris
+/// les espaces insécables).
+pub(crate) fn split_words(s: &str) -> impl Iterator<Item = &str> {
+    s.split_whitespace()
+}
@@ -0,0 +1,284 @@
+//! Module contenant le trait [`Storage`], qui découple la persistance de la base de données
+//! du reste de la bibliothèque.
+//!
+//! Par défaut, la base est sauvegardée dans un unique fichier YAML par [`YamlFileStorage`],
+//! réécrit en entier à chaque sauvegarde. Un autre [`Storage`] peut être donné au bot par
+//! [`crate::Bot::storage`] ; si celui-ci est incrémental (voir [`Storage::is_incremental`]), seuls
+//! les objets ajoutés, modifiés ou supprimés depuis la dernière sauvegarde sont écrits.
+//!
+//! Le contenu de chaque objet reste sérialisé par [`crate::Object::serialize`] et chargé par
+//! [`crate::Object::from_yaml`].
+//!
+//! Les implémentations disponibles dans la bibliothèque sont :
+//! * [`YamlFileStorage`] : fichier YAML unique (par défaut)
+//! * `SqliteStorage` (feature `sqlite`) : base de données SQLite
+
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::fs;
+use std::hash::{Hash, Hasher};
+use std::io::ErrorKind;
+use std::path::Path;
+
+use yaml_rust2::{yaml, Yaml, YamlEmitter, YamlLoader};
+
+use crate::ErrType;
+
+#[cfg(feature = "sqlite")]
+mod sqlite;
+#[cfg(feature = "sqlite")]
+pub use sqlite::SqliteStorage;
+
+/// Moyen de stockage de la base de données du bot.
+///
+/// Les métadonnées regroupent tout ce qui n’est pas un objet de la base de données : elles prennent
+/// la forme d’un dictionnaire YAML contenant notamment la date de dernière mise à jour RSS et les
+/// sauvegardes des salons d’affichage.
+///
+/// Les écritures ([`Storage::upsert`], [`Storage::delete`] et [`Storage::save_meta`]) d’une même
+/// sauvegarde sont suivies d’un appel à [`Storage::commit`], qui peut être utilisé pour les
+/// valider ensemble.
+pub trait Storage: Send {
+    /// Charge toutes les entrées de la base de données, au format YAML.
+    fn load_all(&self) -> Result<Vec<Yaml>, ErrType>;
+
+    /// Charge les métadonnées, ou renvoie [`None`] si aucune sauvegarde n’existe encore.
+    fn load_meta(&self) -> Result<Option<Yaml>, ErrType>;
+
+    /// Ajoute ou remplace l’entrée de l’objet d’identifiant donné.
+    fn upsert(&mut self, id: u64, entry: &Yaml) -> Result<(), ErrType>;
+
+    /// Supprime l’entrée de l’objet d’identifiant donné.
+    fn delete(&mut self, id: u64) -> Result<(), ErrType>;
+
+    /// Remplace les métadonnées.
+    fn save_meta(&mut self, meta: &Yaml) -> Result<(), ErrType>;
+
+    /// Valide les écritures effectuées depuis le dernier appel. Par défaut, ne fait rien.
+    fn commit(&mut self) -> Result<(), ErrType> {
+        Ok(())
+    }
+
+    /// Indique si le stockage conserve les entrées d’une sauvegarde à l’autre. Si ce n’est pas
+    /// le cas, toutes les entrées de la base sont données à [`Storage::upsert`] à chaque
+    /// sauvegarde, avant [`Storage::commit`]. Par défaut, renvoie `true`.
+    fn is_incremental(&self) -> bool {
+        true
+    }
+
+    /// Renvoie le chemin du fichier contenant la sauvegarde, si elle est contenue dans un fichier
+    /// lisible tel quel. Il est envoyé par la commande `/bdd`, et sa taille est affichée par la
+    /// commande `/stats`. Par défaut, renvoie [`None`] : ces commandes signalent alors que la
+    /// sauvegarde n’est pas disponible.
+    fn file_path(&self) -> Option<&str> {
+        None
+    }
+
+    /// Renvoie la sauvegarde de secours du stockage, à lire comme un autre [`Storage`], si le
+    /// stockage en conserve une. Elle est restaurée par la commande `/restaurer_backup`. Par
+    /// défaut, renvoie [`None`] : la commande signale alors que la restauration n’est pas
+    /// disponible.
+    fn backup(&self) -> Option<Box<dyn Storage>> {
+        None
+    }
+}
+
+/// Stockage de la base de données dans un unique fichier YAML, utilisé par défaut par le bot.
+///
+/// Ce stockage n’est pas incrémental : le fichier est réécrit en entier à chaque sauvegarde.
+/// L’écriture est atomique : la sauvegarde est d’abord écrite dans un fichier temporaire qui
+/// remplace ensuite le fichier de sauvegarde. La sauvegarde précédente est conservée dans un
+/// fichier `.bak` (même chemin suivi de `.bak`).
+///
+/// Après l’échec d’une sauvegarde, le fichier `.bak` n’est plus remplacé jusqu’à la prochaine
+/// sauvegarde réussie : une sauvegarde de secours saine n’est ainsi jamais écrasée par un fichier
+/// dont l’écriture a pu être interrompue.
+pub struct YamlFileStorage {
+    path: String,
+    /* Entrées de la sauvegarde en cours, écrites dans le fichier au commit */
+    entries: Vec<Yaml>,
+    meta: yaml::Hash,
+    /* Faux si la dernière sauvegarde a échoué : la sauvegarde de secours n’est alors pas remplacée */
+    last_commit_ok: bool
+}
+
+impl YamlFileStorage {
+    /// Crée un stockage dans le fichier au chemin donné. Le fichier n’est lu qu’au chargement
+    /// de la base de données.
+    pub fn new(path: &str) -> Self {
+        Self {
+            path: path.to_string(),
+            entries: Vec::new(),
+            meta: yaml::Hash::new(),
+            last_commit_ok: true
+        }
+    }
+
+    /// Renvoie le chemin du fichier de sauvegarde.
+    pub fn path(&self) -> &str {
+        &self.path
+    }
+
+    /// Renvoie le chemin de la sauvegarde de secours, contenant l’avant-dernière sauvegarde.
+    pub fn backup_path(&self) -> String {
+        format!("{}.bak", self.path)
+    }
+
+    /* Écrit les entrées et métadonnées en attente dans un fichier temporaire qui remplace ensuite
+       le fichier de sauvegarde, en conservant l’ancien fichier comme sauvegarde de secours si la
+       sauvegarde précédente a réussi. */
+    fn _write(&mut self) -> Result<(), ErrType> {
+        let mut yaml_out = yaml::Hash::new();
+        yaml_out.insert(Yaml::String("entries".into()), Yaml::Array(std::mem::take(&mut self.entries)));
+        yaml_out.extend(std::mem::take(&mut self.meta));
+        let mut out_str = String::new();
+        YamlEmitter::new(&mut out_str).dump(&Yaml::Hash(yaml_out))?;
+        let tmp_file = format!("{}.tmp", self.path);
+        fs::write(&tmp_file, &out_str)?;
+        if self.last_commit_ok && Path::new(&self.path).exists() {
+            fs::copy(&self.path, self.backup_path())?;
+        }
+        fs::rename(&tmp_file, &self.path)?;
+        Ok(())
+    }
+
+    /* Lit le fichier de sauvegarde. Renvoie None si le fichier n’existe pas. */
+    fn _read(&self) -> Result<Option<Yaml>, ErrType> {
+        match fs::read_to_string(&self.path) {
+            Ok(data) => Ok(YamlLoader::load_from_str(data.as_str())?.into_iter().next()),
+            Err(e) if e.kind() == ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(e.into())
+        }
+    }
+}
+
+impl Storage for YamlFileStorage {
+    fn load_all(&self) -> Result<Vec<Yaml>, ErrType> {
+        Ok(match self._read()? {
+            Some(data) => data["entries"].as_vec()
+                .ok_or(ErrType::YamlParseError("Dans les données, entries n’est pas un tableau.".to_string()))?
+                .clone(),
+            None => Vec::new()
+        })
+    }
+
+    fn load_meta(&self) -> Result<Option<Yaml>, ErrType> {
+        Ok(self._read()?.and_then(|data| data.into_hash()).map(|mut data| {
+            data.remove(&Yaml::String("entries".into()));
+            Yaml::Hash(data)
+        }))
+    }
+
+    fn upsert(&mut self, _id: u64, entry: &Yaml) -> Result<(), ErrType> {
+        self.entries.push(entry.clone());
+        Ok(())
+    }
+
+    fn delete(&mut self, _id: u64) -> Result<(), ErrType> {
+        /* Les entrées absentes de la sauvegarde ne sont simplement pas réécrites */
+        Ok(())
+    }
+
+    fn save_meta(&mut self, meta: &Yaml) -> Result<(), ErrType> {
+        self.meta = meta.as_hash().cloned().unwrap_or_default();
+        Ok(())
+    }
+
+    fn commit(&mut self) -> Result<(), ErrType> {
+        let resultat = self._write();
+        self.last_commit_ok = resultat.is_ok();
+        resultat
+    }
+
+    fn is_incremental(&self) -> bool {
+        false
+    }
+
+    fn file_path(&self) -> Option<&str> {
+        Some(&self.path)
+    }
+
+    fn backup(&self) -> Option<Box<dyn Storage>> {
+        Some(Box::new(YamlFileStorage::new(&self.backup_path())))
+    }
+}
+
+/* Stockage du bot et empreinte de chaque entrée au moment de sa dernière écriture, permettant de
+   n’écrire que les entrées modifiées, ainsi que l’empreinte de la dernière sauvegarde complète,
+   permettant de ne rien écrire si rien n’a changé. */
+pub(crate) struct StorageState {
+    pub(crate) backend: Box<dyn Storage>,
+    written: HashMap<u64, u64>,
+    last_save: Option<u64>
+}
+
+impl StorageState {
+    pub(crate) fn new(backend: Box<dyn Storage>) -> Self {
+        Self {
+            backend,
+            written: HashMap::new(),
+            last_save: None
+        }
+    }
+
+    /* Enregistre les entrées telles qu’elles viennent d’être chargées, sans les écrire */
+    pub(crate) fn mark_written(&mut self, entries: impl Iterator<Item = (u64, Yaml)>) -> Result<(), ErrType> {
+        if self.backend.is_incremental() {
+            self.written = entries.map(|(id, entry)| Ok((id, _empreinte(&entry)?)))
+                .collect::<Result<_, ErrType>>()?;
+        }
+        Ok(())
+    }
+
+    /* Écrit les entrées nouvelles ou modifiées (toutes si le stockage n’est pas incrémental),
+       supprime les entrées absentes, puis écrit les métadonnées et valide le tout. N’écrit rien si
+       les entrées et les métadonnées sont identiques à celles de la dernière sauvegarde. */
+    pub(crate) fn save(&mut self, entries: impl Iterator<Item = (u64, Yaml)>, meta: &Yaml) -> Result<(), ErrType> {
+        let entries: Vec<(u64, u64, Yaml)> = entries
+            .map(|(id, entry)| Ok((id, _empreinte(&entry)?, entry)))
+            .collect::<Result<_, ErrType>>()?;
+        /* Empreinte indépendante de l’ordre des entrées */
+        let empreinte_sauvegarde = entries.iter()
+            .fold(_empreinte(meta)?, |acc, (id, empreinte, _)| {
+                let mut hasher = DefaultHasher::new();
+                (id, empreinte).hash(&mut hasher);
+                acc.wrapping_add(hasher.finish())
+            });
+        if self.last_save == Some(empreinte_sauvegarde) {
+            return Ok(());
+        }
+        if !self.backend.is_incremental() {
+            for (id, _, entry) in entries {
+                self.backend.upsert(id, &entry)?;
+            }
+            self.backend.save_meta(meta)?;
+            self.backend.commit()?;
+            self.last_save = Some(empreinte_sauvegarde);
+            return Ok(());
+        }
+        let mut written = HashMap::new();
+        for (id, empreinte, entry) in entries {
+            if self.written.get(&id) != Some(&empreinte) {
+                self.backend.upsert(id, &entry)?;
+            }
+            written.insert(id, empreinte);
+        }
+        for id in self.written.keys().filter(|id| !written.contains_key(id)) {
+            self.backend.delete(*id)?;
+        }
+        self.backend.save_meta(meta)?;
+        self.backend.commit()?;
+        self.written = written;
+        self.last_save = Some(empreinte_sauvegarde);
+        Ok(())
+    }
+}
+
+/* Empreinte d’une entrée YAML, pour détecter ses modifications */
+fn _empreinte(entry: &Yaml) -> Result<u64, ErrType> {
+    let mut out = String::new();
+    YamlEmitter::new(&mut out).dump(entry)?;
+    let mut hasher = DefaultHasher::new();
+    out.hash(&mut hasher);
+    Ok(hasher.finish())
+}
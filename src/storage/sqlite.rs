@@ -0,0 +1,97 @@
+//! Implémentation de [`Storage`] dans une base de données SQLite.
+
+use rusqlite::{params, Connection, OptionalExtension};
+use yaml_rust2::{Yaml, YamlEmitter, YamlLoader};
+
+use super::Storage;
+use crate::ErrType;
+
+/// Stockage de la base de données du bot dans une base SQLite (feature `sqlite`).
+///
+/// Chaque objet est stocké dans une ligne de la table `entries`, au format YAML produit par
+/// [`crate::Object::serialize`]. Les métadonnées sont stockées dans la table `meta`. Les écritures
+/// d’une même sauvegarde sont regroupées dans une transaction, annulée si l’une d’elles échoue.
+pub struct SqliteStorage {
+    connection: Connection,
+    /* Écritures de la sauvegarde en cours, appliquées dans une transaction au commit */
+    pending: Vec<Ecriture>
+}
+
+/* Écriture en attente de Storage::commit */
+enum Ecriture {
+    Upsert(u64, String),
+    Delete(u64),
+    Meta(String)
+}
+
+impl SqliteStorage {
+    /// Ouvre (ou crée) la base SQLite au chemin donné et crée les tables si nécessaire.
+    pub fn open(path: &str) -> Result<Self, ErrType> {
+        let connection = Connection::open(path)?;
+        connection.execute_batch(
+            "CREATE TABLE IF NOT EXISTS entries (id INTEGER PRIMARY KEY, data TEXT NOT NULL);
+             CREATE TABLE IF NOT EXISTS meta (key TEXT PRIMARY KEY, data TEXT NOT NULL);"
+        )?;
+        Ok(Self {connection, pending: Vec::new()})
+    }
+}
+
+/* Lit un document YAML stocké dans la base */
+fn _parse(data: &str) -> Result<Yaml, ErrType> {
+    YamlLoader::load_from_str(data)?.into_iter().next()
+        .ok_or(ErrType::YamlParseError("Entrée SQLite vide.".to_string()))
+}
+
+/* Écrit un document YAML pour le stocker dans la base */
+fn _emit(yaml: &Yaml) -> Result<String, ErrType> {
+    let mut out = String::new();
+    YamlEmitter::new(&mut out).dump(yaml)?;
+    Ok(out)
+}
+
+impl Storage for SqliteStorage {
+    fn load_all(&self) -> Result<Vec<Yaml>, ErrType> {
+        let mut statement = self.connection.prepare("SELECT data FROM entries")?;
+        let rows = statement.query_map([], |row| row.get::<_, String>(0))?;
+        rows.map(|data| _parse(&data?)).collect()
+    }
+
+    fn load_meta(&self) -> Result<Option<Yaml>, ErrType> {
+        self.connection.query_row("SELECT data FROM meta WHERE key = 'meta'", [], |row| row.get::<_, String>(0))
+            .optional()?
+            .map(|data| _parse(&data)).transpose()
+    }
+
+    fn upsert(&mut self, id: u64, entry: &Yaml) -> Result<(), ErrType> {
+        self.pending.push(Ecriture::Upsert(id, _emit(entry)?));
+        Ok(())
+    }
+
+    fn delete(&mut self, id: u64) -> Result<(), ErrType> {
+        self.pending.push(Ecriture::Delete(id));
+        Ok(())
+    }
+
+    fn save_meta(&mut self, meta: &Yaml) -> Result<(), ErrType> {
+        self.pending.push(Ecriture::Meta(_emit(meta)?));
+        Ok(())
+    }
+
+    fn commit(&mut self) -> Result<(), ErrType> {
+        let pending = std::mem::take(&mut self.pending);
+        /* La transaction est annulée à sa destruction si elle n’a pas été validée */
+        let transaction = self.connection.transaction()?;
+        for ecriture in pending {
+            match ecriture {
+                Ecriture::Upsert(id, data) => transaction.execute(
+                    "INSERT OR REPLACE INTO entries (id, data) VALUES (?1, ?2)", params![id as i64, data])?,
+                Ecriture::Delete(id) => transaction.execute(
+                    "DELETE FROM entries WHERE id = ?1", params![id as i64])?,
+                Ecriture::Meta(data) => transaction.execute(
+                    "INSERT OR REPLACE INTO meta (key, data) VALUES ('meta', ?1)", params![data])?
+            };
+        }
+        transaction.commit()?;
+        Ok(())
+    }
+}
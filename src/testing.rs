@@ -0,0 +1,103 @@
+//! Module optionnel (feature `test-utils`) facilitant l’écriture de tests sur la logique métier
+//! d’un bot, sans client Discord.
+//!
+//! [`bot_with`] construit un [`Bot`] sans connexion réseau, avec une base de données pré-remplie et
+//! un stockage en mémoire ([`MemoryStorage`]). Les méthodes ne nécessitant pas serenity
+//! ([`Bot::search`], [`Bot::archive`], [`Bot::annuler`], [`Bot::find_duplicates`],
+//! [`Bot::next_id`], [`Bot::save`]…) peuvent alors être appelées directement dans des tests.
+//!
+//! Les méthodes nécessitant un contexte Discord (commandes, salons d’affichage, logs) ne sont pas
+//! couvertes par ce module.
+//!
+//! ```ignore
+//! let mut bot = fondabots_lib::testing::bot_with(vec![ecrit_1, ecrit_2]);
+//! assert_eq!(bot.search("fonda").len(), 1);
+//! ```
+
+use std::collections::BTreeMap;
+
+use yaml_rust2::Yaml;
+
+use crate::object::Object;
+use crate::storage::Storage;
+use crate::{Bot, ErrType};
+
+/// Stockage de la base de données en mémoire, sans aucune écriture sur le disque.
+///
+/// Les écritures ne sont visibles qu’après [`Storage::commit`], comme pour un stockage
+/// transactionnel.
+#[derive(Clone, Debug, Default)]
+pub struct MemoryStorage {
+    entries: BTreeMap<u64, Yaml>,
+    meta: Option<Yaml>,
+    /* Écritures en attente de commit : None pour une suppression */
+    pending: Vec<(u64, Option<Yaml>)>,
+    pending_meta: Option<Yaml>
+}
+
+impl MemoryStorage {
+    /// Crée un stockage vide.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Renvoie les entrées validées, triées par identifiant.
+    pub fn entries(&self) -> &BTreeMap<u64, Yaml> {
+        &self.entries
+    }
+
+    /// Renvoie les métadonnées validées, si elles existent.
+    pub fn meta(&self) -> Option<&Yaml> {
+        self.meta.as_ref()
+    }
+}
+
+impl Storage for MemoryStorage {
+    fn load_all(&self) -> Result<Vec<Yaml>, ErrType> {
+        Ok(self.entries.values().cloned().collect())
+    }
+
+    fn load_meta(&self) -> Result<Option<Yaml>, ErrType> {
+        Ok(self.meta.clone())
+    }
+
+    fn upsert(&mut self, id: u64, entry: &Yaml) -> Result<(), ErrType> {
+        self.pending.push((id, Some(entry.clone())));
+        Ok(())
+    }
+
+    fn delete(&mut self, id: u64) -> Result<(), ErrType> {
+        self.pending.push((id, None));
+        Ok(())
+    }
+
+    fn save_meta(&mut self, meta: &Yaml) -> Result<(), ErrType> {
+        self.pending_meta = Some(meta.clone());
+        Ok(())
+    }
+
+    fn commit(&mut self) -> Result<(), ErrType> {
+        for (id, entry) in self.pending.drain(..) {
+            match entry {
+                Some(entry) => self.entries.insert(id, entry),
+                None => self.entries.remove(&id)
+            };
+        }
+        if let Some(meta) = self.pending_meta.take() {
+            self.meta = Some(meta);
+        }
+        Ok(())
+    }
+}
+
+/// Construit un bot sans connexion à Discord, dont la base de données contient les objets donnés
+/// (indexés par [`Object::get_id`]) et dont le stockage est un [`MemoryStorage`] vide.
+///
+/// Le bot n’est pas initialisé par [`Bot::setup`] : les salons d’affichage, les salons absolus et
+/// le salon des logs sont absents.
+pub fn bot_with<T: Object>(objects: impl IntoIterator<Item = T>) -> Bot<T> {
+    let mut bot = Bot::default().storage(MemoryStorage::new());
+    bot.database = objects.into_iter().map(|object| (object.get_id(), object)).collect();
+    bot.rebuild_search_index();
+    bot
+}
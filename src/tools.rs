@@ -3,12 +3,13 @@
 //! aucun lien entre eux pour la plupart, sauf spécification contraire.
 
 use crate::{Bot, DataType, ErrType, Object};
-use chrono::{DateTime, NaiveDate, Utc};
+use chrono::{DateTime, NaiveDate, NaiveDateTime, TimeZone, Utc};
 use poise::futures_util::FutureExt;
 use poise::{serenity_prelude as serenity, BoxFuture, Command, Context, CreateReply};
-use serenity::all::{ChannelId, CreateEmbed, CreateEmbedFooter, GuildChannel, RoleId, Timestamp, User, UserId};
-use serenity::all::{Context as SerenityContext, GetMessages, Message, MessageId};
+use serenity::all::{ActionRow, ActionRowComponent, AutocompleteChoice, Button, ButtonKind, ButtonStyle, ChannelId, ComponentInteractionCollector, CreateActionRow, CreateButton, CreateEmbed, CreateEmbedFooter, CreateInteractionResponse, CreateInteractionResponseMessage, GuildChannel, RoleId, Timestamp, User, UserId};
+use serenity::all::{Context as SerenityContext, CreateAttachment, GetMessages, Message, MessageId};
 use std::future::Future;
+use std::sync::{Arc, OnceLock};
 use unicode_normalization::UnicodeNormalization;
 
 /// Trait utilisé pour des objets de l’API Discord nécessitant un chargement après leur définition.
@@ -103,19 +104,102 @@ macro_rules! try_loop {
     ($e:expr, $m:literal) => (match $e {
         Ok(val) => val,
         Err(err) => {
-            eprintln!($m);
-            eprintln!("{err}");
+            $crate::tracing::error!($m);
+            $crate::tracing::error!("{err}");
             continue;
         }
     })
 }
 
+/// Options de normalisation des chaînes de caractères utilisées par [`basicize_with`].
+///
+/// La valeur par défaut correspond au comportement de [`basicize`]. En cas d’utilisation directe
+/// de la structure, incluez systématiquement `..NormalizeOptions::default()` (voir
+/// [`crate::command_data::CommandData`] pour la raison).
+#[derive(Clone, Debug)]
+pub struct NormalizeOptions {
+    /// Met la chaîne en minuscules. Cette étape est effectuée en premier.
+    pub lowercase: bool,
+    /// Remplacements effectués dans l’ordre après la mise en minuscules : chaque occurrence du
+    /// premier élément est remplacée par le second.
+    pub substitutions: Vec<(String, String)>,
+    /// Supprime les diacritiques et les caractères non-ascii qui ne sont pas alphanumériques.
+    /// Cette étape est effectuée en dernier.
+    pub strip_diacritics: bool
+}
+
+impl Default for NormalizeOptions {
+    fn default() -> Self {
+        Self {
+            lowercase: true,
+            substitutions: [
+                /* Apostrophes et guillemets typographiques */
+                ("`", "'"), ("’", "'"), ("‘", "'"), ("\u{2BC}", "'"), ("“", "\""), ("”", "\""), ("„", "\""),
+                /* Tirets */
+                ("\u{2010}", "-"), ("\u{2011}", "-"), ("\u{2012}", "-"), ("–", "-"), ("—", "-"), ("\u{2015}", "-"), ("\u{2212}", "-"),
+                /* Espaces insécables et fines */
+                ("\u{A0}", " "), ("\u{2009}", " "), ("\u{202F}", " "),
+                /* Ligatures, non décomposées par la suppression des diacritiques */
+                ("œ", "oe"), ("æ", "ae"), ("ﬁ", "fi"), ("ﬂ", "fl")
+            ].into_iter().map(|(from, to)| (from.to_string(), to.to_string())).collect(),
+            strip_diacritics: true
+        }
+    }
+}
+
 /// Simplifie une chaîne de caractères en la mettant en minuscules, remplaçant certains caractères
 /// en caractères équivalents plus communs, et en supprimant les diacritiques et caractères non-ascii.
+///
+/// Les remplacements par défaut ramènent les apostrophes et guillemets typographiques à leur
+/// équivalent droit (`’` devient `'`), les tirets (demi-cadratin, cadratin, insécable…) au trait
+/// d’union, les espaces insécables à l’espace simple, et décomposent les ligatures (`œ` devient
+/// `oe`). Ainsi, « cœur » est trouvé en cherchant « coeur », et « l’anomalie » en cherchant
+/// « l'anomalie ».
+///
+/// Raccourci pour [`basicize_with`] avec les options par défaut de [`NormalizeOptions`].
 pub fn basicize(s: &str) -> String {
-    s.to_lowercase().replace("`", "'").replace(" ", " ").nfd()
-        .filter(|c| c.is_ascii() || c.is_alphanumeric())
-        .collect()
+    static DEFAULT_OPTIONS: OnceLock<NormalizeOptions> = OnceLock::new();
+    basicize_with(s, DEFAULT_OPTIONS.get_or_init(NormalizeOptions::default))
+}
+
+/// Simplifie une chaîne de caractères selon les étapes activées dans les [`NormalizeOptions`]
+/// données.
+pub fn basicize_with(s: &str, options: &NormalizeOptions) -> String {
+    let s = if options.lowercase { s.to_lowercase() } else { s.to_string() };
+    let s = options.substitutions.iter()
+        .fold(s, |s, (from, to)| s.replace(from.as_str(), to.as_str()));
+    if options.strip_diacritics {
+        s.nfd()
+            .filter(|c| c.is_ascii() || c.is_alphanumeric())
+            .collect()
+    } else {
+        s
+    }
+}
+
+/// Décrit les différences entre deux états d’un objet ([`Object::diff`]), une ligne
+/// « champ : avant → après » par champ modifié. Renvoie [`None`] si aucune différence n’est
+/// signalée.
+pub fn describe_diff<T: Object>(avant: &T, apres: &T) -> Option<String> {
+    let lignes: Vec<String> = avant.diff(apres).into_iter()
+        .map(|(champ, avant, apres)| format!("{champ} : {avant} → {apres}"))
+        .collect();
+    (!lignes.is_empty()).then(|| lignes.join("\n"))
+}
+
+/// Renvoie la clé de comparaison d’un nom pour la détection des doublons
+/// ([`crate::Bot::find_duplicates`]) : deux objets dont les noms ont la même clé sont considérés
+/// comme des doublons.
+///
+/// Avec [`crate::DuplicateMatching::Normalized`], le nom est normalisé par [`basicize_with`] et
+/// ses espaces consécutifs sont réduits à un seul ; avec [`crate::DuplicateMatching::Exact`], il
+/// est renvoyé tel quel.
+pub fn duplicate_key(name: &str, matching: crate::DuplicateMatching, options: &NormalizeOptions) -> String {
+    match matching {
+        crate::DuplicateMatching::Normalized => basicize_with(name, options)
+            .split_whitespace().collect::<Vec<_>>().join(" "),
+        crate::DuplicateMatching::Exact => name.to_string()
+    }
 }
 
 /// Fonction auxiliaire pour toutes les commandes prenant un objet en argument. Celle-ci va chercher
@@ -124,25 +208,27 @@ pub fn basicize(s: &str) -> String {
 ///
 /// Si le critère de recherche est un nombre, il sera interprété comme l’identifiant de l’objet
 /// recherché et la recherche par nom n’aura pas lieu.
+///
+/// Pour les commandes agissant sur tous les objets correspondants, voir [`get_objects`].
 pub async fn get_object<T: Object>(ctx: &Context<'_, DataType<T>, ErrType>, bot: &Bot<T>, c: &String) -> Result<Option<u64>, ErrType> {
     if let Ok(id) = c.parse() {
         if bot.database.contains_key(&id) {
             Ok(Some(id))
         } else {
             ctx.send(CreateReply::default()
-                .content("Aucun objet n’existe avec cet identifiant.")).await?;
+                .content(bot.tr("objet.id_inconnu", &[]))).await?;
             Ok(None)
         }
     } else {
         let res = bot.search(c);
         if res.len() > 1 {
             ctx.send(CreateReply::default()
-                .content("Le nom donné référence plus d’un objet. Merci d’affiner le critère ou de rechercher par ID."))
+                .content(bot.tr("objet.ambigu", &[])))
                 .await?;
             Ok(None)
         } else if res.len() == 0 {
             ctx.send(CreateReply::default()
-                .content("Aucun objet trouvé.")).await?;
+                .content(bot.tr("objet.introuvable", &[]))).await?;
             Ok(None)
         } else {
             Ok(Some(res[0].clone()))
@@ -150,14 +236,226 @@ pub async fn get_object<T: Object>(ctx: &Context<'_, DataType<T>, ErrType>, bot:
     }
 }
 
-/// Lit un [`Timestamp`] au format `%d/%m/%Y` depuis une chaîne de caractères. Renvoie [`None`]
-/// si le format de la chaîne de caractères est incorrect.
+/// Variante de [`get_object`] pour les commandes d’action groupée : renvoie tous les objets
+/// correspondant au critère au lieu d’exiger un objet unique.
+///
+/// Si le critère est un nombre, il est interprété comme l’identifiant d’un objet. Sinon, les
+/// objets sont ceux trouvés par [`Bot::search`]. Si plus de `seuil_confirmation` objets sont
+/// trouvés, une confirmation est demandée (voir [`confirm`]) : la question posée est formée par
+/// `question` d’après le nombre d’objets trouvés. Un message est envoyé et un vecteur vide est
+/// renvoyé si aucun objet n’est trouvé ou si l’opération n’est pas confirmée.
+///
+/// <div class="warning">
+/// Contrairement à [`get_object`], cette fonction prend elle-même le verrou sur les données du
+/// bot, qui ne doit donc pas être tenu pendant l’appel (voir [`confirm`]).
+/// </div>
+pub async fn get_objects<T: Object>(
+    ctx: &Context<'_, DataType<T>, ErrType>,
+    c: &str,
+    seuil_confirmation: usize,
+    question: impl FnOnce(&Bot<T>, usize) -> String + Send
+) -> Result<Vec<u64>, ErrType> {
+    let (objects, message) = {
+        let bot = ctx.data().lock().await;
+        match c.parse() {
+            Ok(id) if bot.database.contains_key(&id) => (vec![id], None),
+            Ok(_) => (Vec::new(), Some(bot.tr("objet.id_inconnu", &[]))),
+            Err(_) => {
+                let objects: Vec<u64> = bot.search(c).into_iter().copied().collect();
+                let message = objects.is_empty().then(|| bot.tr("objet.introuvable", &[]));
+                (objects, message)
+            }
+        }
+    };
+    if let Some(message) = message {
+        ctx.send(CreateReply::default().content(message)).await?;
+        return Ok(Vec::new());
+    }
+    if objects.len() > seuil_confirmation {
+        let question = question(&*ctx.data().lock().await, objects.len());
+        if !confirm(ctx, question).await? {
+            return Ok(Vec::new());
+        }
+    }
+    Ok(objects)
+}
+
+/// Fonction d’autocomplétion des noms d’objets, à utiliser sur les paramètres de commande
+/// identifiant un objet (voir [`get_object`]) avec l’attribut
+/// `#[autocomplete = "tools::autocomplete_object_name"]`.
+///
+/// Les suggestions sont les objets trouvés par [`Bot::search`], triés par pertinence : nom
+/// identique au texte entré, puis nom commençant par ce texte, puis nom dont un mot commence par
+/// ce texte, puis le reste, par ordre alphabétique à pertinence égale. La valeur renvoyée à la
+/// commande est l’identifiant de l’objet, ce qui évite toute ambiguïté. Si aucun texte n’est entré,
+/// les objets les plus récents sont proposés. Le nombre de suggestions est limité à 25 (limite de
+/// Discord).
+pub async fn autocomplete_object_name<T: Object>(ctx: Context<'_, DataType<T>, ErrType>, partial: &str) -> Vec<AutocompleteChoice> {
+    let bot = ctx.data().lock().await;
+    let partial = basicize_with(partial.trim(), &bot.normalize_options);
+    let mut choices: Vec<(u8, &T)> = if partial.is_empty() {
+        sort_by_date(bot.database.iter().collect()).into_iter()
+            .map(|(_, object)| (0, object)).collect()
+    } else {
+        bot.search(&partial).into_iter().filter_map(|id| bot.database.get(id)).map(|object| {
+            let name = basicize_with(object.get_name(), &bot.normalize_options);
+            (if name == partial {
+                0
+            } else if name.starts_with(&partial) {
+                1
+            } else if name.split(" ").any(|mot| mot.starts_with(&partial)) {
+                2
+            } else {
+                3
+            }, object)
+        }).collect()
+    };
+    if !partial.is_empty() {
+        choices.sort_by(|(score_a, a), (score_b, b)| score_a.cmp(score_b).then_with(|| a.get_name().cmp(b.get_name())));
+    }
+    choices.into_iter().take(25).map(|(_, object)| {
+        AutocompleteChoice::new(object.get_name().chars().take(100).collect::<String>(), object.get_id().to_string())
+    }).collect()
+}
+
+/// Lit un [`Timestamp`] au format `%d/%m/%Y` depuis une chaîne de caractères, à minuit UTC.
+/// Renvoie [`None`] si le format de la chaîne de caractères est incorrect.
+///
+/// Pour interpréter la date dans le fuseau horaire du bot, utiliser [`Timezone::parse_date`]
+/// (voir [`Bot::get_timezone`]).
 pub fn parse_date(date: String) -> Option<Timestamp> {
-    Some(Timestamp::from(DateTime::<Utc>::from_naive_utc_and_offset(
-        NaiveDate::parse_from_str(
-            date.as_str(), "%d/%m/%Y").ok().and_then(|d| {
-            d.and_hms_opt(0, 0, 0)
-        }).unwrap(), Utc)))
+    Timezone::default().parse_date(&date)
+}
+
+/// Fuseau horaire utilisé pour interpréter les dates saisies et formater les dates affichées
+/// (voir [`Bot::timezone`]). Par défaut, UTC.
+///
+/// Tout fuseau implémentant [`TimeZone`] peut être utilisé, en particulier ceux de la
+/// bibliothèque `chrono-tz` (par exemple `chrono_tz::Europe::Paris`), qui gèrent l’heure d’été.
+#[derive(Clone)]
+pub struct Timezone {
+    /* Conversion d’une date locale en UTC, None si elle n’existe pas dans le fuseau */
+    to_utc: Arc<dyn Fn(NaiveDateTime) -> Option<DateTime<Utc>> + Send + Sync>,
+    /* Conversion d’une date UTC en date locale */
+    to_local: Arc<dyn Fn(DateTime<Utc>) -> NaiveDateTime + Send + Sync>
+}
+
+impl Default for Timezone {
+    fn default() -> Self {
+        Self::new(Utc)
+    }
+}
+
+impl Timezone {
+    /// Crée un fuseau horaire d’après un fuseau de `chrono` ou de `chrono-tz`.
+    pub fn new<Tz: TimeZone + Send + Sync + 'static>(tz: Tz) -> Self {
+        let tz_local = tz.clone();
+        Self {
+            to_utc: Arc::new(move |date| tz.from_local_datetime(&date).earliest().map(|date| date.with_timezone(&Utc))),
+            to_local: Arc::new(move |date| date.with_timezone(&tz_local).naive_local())
+        }
+    }
+
+    /// Lit un [`Timestamp`] au format `%d/%m/%Y` depuis une chaîne de caractères, à minuit dans
+    /// le fuseau horaire. Renvoie [`None`] si le format de la chaîne de caractères est incorrect.
+    pub fn parse_date(&self, date: &str) -> Option<Timestamp> {
+        let minuit = NaiveDate::parse_from_str(date, "%d/%m/%Y").ok()?.and_hms_opt(0, 0, 0)?;
+        (self.to_utc)(minuit).map(Timestamp::from)
+    }
+
+    /// Formate la date donnée au format `%d/%m/%Y` dans le fuseau horaire.
+    pub fn format_date(&self, date: &Timestamp) -> String {
+        self.to_local(date).format("%d/%m/%Y").to_string()
+    }
+
+    /// Formate la date et l’heure données au format `%d/%m/%Y %H:%M` dans le fuseau horaire.
+    pub fn format_datetime(&self, date: &Timestamp) -> String {
+        self.to_local(date).format("%d/%m/%Y %H:%M").to_string()
+    }
+
+    /* Date locale correspondant au Timestamp donné */
+    fn to_local(&self, date: &Timestamp) -> NaiveDateTime {
+        let utc = DateTime::<Utc>::from_timestamp(date.unix_timestamp(), 0).unwrap_or_default();
+        (self.to_local)(utc)
+    }
+}
+
+/// Requête de recherche avancée, produite par [`parse_advanced_query`] et interprétée par
+/// [`Bot::advanced_search`].
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct AdvancedQuery {
+    /// Critère de recherche sur le nom (voir [`Bot::search`]). Vide si aucun critère n’est donné.
+    pub nom: String,
+    /// Date avant laquelle les objets doivent dater, exclue.
+    pub avant: Option<Timestamp>,
+    /// Date à partir de laquelle les objets doivent dater, incluse.
+    pub apres: Option<Timestamp>,
+    /// Filtres sur les autres propriétés, sous la forme (clé, valeur), interprétés par
+    /// [`Object::matches_filter`].
+    pub filtres: Vec<(String, String)>
+}
+
+/// Lit une requête de recherche avancée. En cas d’erreur, renvoie le terme invalide.
+///
+/// La requête est une suite de termes séparés par des espaces ; un terme peut contenir des
+/// espaces s’il est entre guillemets droits (`"`). Chaque terme est de l’une des formes :
+/// * `nom:valeur` ou `valeur` : mot (ou mots entre guillemets) à rechercher dans le nom, avec
+///   la syntaxe de [`Bot::search`] (un mot précédé de `-` est exclu) ;
+/// * `avant:jj/mm/aaaa` : objets datant d’avant ce jour ;
+/// * `apres:jj/mm/aaaa` (ou `après:`) : objets datant de ce jour ou après ;
+/// * `cle:valeur` : filtre sur une autre propriété, interprété par [`Object::matches_filter`].
+///
+/// Exemple : `nom:"la fondation" etat:attente avant:01/01/2024`.
+///
+/// Les dates sont interprétées à minuit UTC ; voir [`parse_advanced_query_with`] pour utiliser un
+/// autre fuseau horaire.
+pub fn parse_advanced_query(requete: &str) -> Result<AdvancedQuery, String> {
+    parse_advanced_query_with(requete, &Timezone::default())
+}
+
+/// Équivalent de [`parse_advanced_query`] interprétant les dates dans le fuseau horaire donné
+/// (voir [`Bot::get_timezone`]).
+pub fn parse_advanced_query_with(requete: &str, timezone: &Timezone) -> Result<AdvancedQuery, String> {
+    let mut termes = Vec::new();
+    let mut terme = String::new();
+    let mut guillemets = false;
+    for c in requete.chars() {
+        match c {
+            '"' => guillemets = !guillemets,
+            c if c.is_whitespace() && !guillemets => if !terme.is_empty() {
+                termes.push(std::mem::take(&mut terme));
+            },
+            c => terme.push(c)
+        }
+    }
+    if guillemets {
+        return Err(requete.to_string());
+    }
+    if !terme.is_empty() {
+        termes.push(terme);
+    }
+
+    let mut query = AdvancedQuery::default();
+    let mut noms = Vec::new();
+    for terme in termes {
+        match terme.split_once(':') {
+            Some(("nom", valeur)) => noms.push(valeur.to_string()),
+            Some(("avant", date)) => query.avant = Some(timezone.parse_date(date).ok_or(terme.clone())?),
+            Some(("apres" | "après", date)) => query.apres = Some(timezone.parse_date(date).ok_or(terme.clone())?),
+            Some((cle, valeur)) if !cle.is_empty() && !valeur.is_empty() => query.filtres.push((cle.to_string(), valeur.to_string())),
+            Some(_) => return Err(terme),
+            None => noms.push(terme)
+        }
+    }
+    query.nom = noms.join(" ");
+    Ok(query)
+}
+
+/// Fonction auxiliaire pour [`Object::matches_filter`] : vérifie que la propriété [`Field`](crate::object::Field) de
+/// l’objet correspond à la valeur donnée, lue par [`FromStr`](std::str::FromStr). Renvoie
+/// `Some(false)` si la valeur ne correspond à aucune valeur du champ.
+pub fn field_matches<T: Object, F: crate::object::Field<T>>(obj: &T, valeur: &str) -> Option<bool> {
+    Some(valeur.parse::<F>().is_ok_and(|field| F::comply_with(obj, &Some(field))))
 }
 
 /// Fonction auxiliaire pour la création d’une commande alias d’une autre commande. Pour l’utiliser,
@@ -169,6 +467,16 @@ pub fn alias<T: Object>(name: &str, mut com: Command<DataType<T>, ErrType>) -> C
     com
 }
 
+/// Crée une commande alias par nom donné, à la manière de [`alias`]. Pour l’utiliser, il suffit
+/// d’étendre la liste des commandes avec `aliases(&["alias_1", "alias_2"], commande_originale)`.
+///
+/// Les commandes de poise n’étant pas clonables, la fonction de la commande originale est donnée
+/// elle-même et appelée pour chaque alias : chaque alias a donc ses propres paramètres,
+/// `custom_data` et vérifications, identiques à ceux de la commande originale.
+pub fn aliases<T: Object>(names: &[&str], com: impl Fn() -> Command<DataType<T>, ErrType>) -> Vec<Command<DataType<T>, ErrType>> {
+    names.iter().map(|name| alias(name, com())).collect()
+}
+
 /// Transforme une liste de chaînes de caractères en une liste d’embeds Discord d’après un
 /// template (CreateEmbed déjà pré-rempli, qui sera copié).
 ///
@@ -188,27 +496,59 @@ pub fn get_multimessages(pages: Vec<String>, template: CreateEmbed) -> Vec<Creat
     }).collect()
 }
 
-/* Fonction de fusion du tri fusion */
-fn _sort_merge<'a, T: Object>(mut a: Vec<(&'a u64, &'a T)>, mut b: Vec<(&'a u64, &'a T)>) -> Vec<(&'a u64, &'a T)> {
-    let mut res = Vec::new();
-    while !(a.is_empty() && b.is_empty()) {
-        if let (Some((_, ecrit_a)), Some((_, ecrit_b))) = (a.last(), b.last()) {
-            res.push(if ecrit_a.get_date() > ecrit_b.get_date() { &mut a } else { &mut b }.pop().unwrap());
-        } else {
-            while !if a.is_empty() { &b } else { &a }.is_empty() {
-                res.push(if a.is_empty() { &mut b } else { &mut a }.pop().unwrap());
-            }
-        }
-    }
-    res
+/// Transforme une liste de pages de champs (nom et valeur) en une liste d’embeds Discord d’après
+/// un template, à la manière de [`get_multimessages`] mais en remplissant les champs des embeds
+/// ([`CreateEmbed::fields`]) au lieu de leur description. Les embeds renvoyés s’envoient de la même
+/// manière, avec [`Bot::send_embed`].
+///
+/// Sont affectés le `footer` ([`CreateEmbed::footer`]) pour la liste des pages et les champs de
+/// chaque embed, ajoutés à ceux éventuellement déjà présents dans le template. Attention : il est
+/// attendu que chaque page respecte les limites de Discord (25 champs, 256 caractères par nom,
+/// 1 024 caractères par valeur et 6 000 caractères par embed), sans quoi il y aura une erreur à
+/// l’envoi ; cette fonction ne le vérifie pas.
+pub fn get_multimessages_fields(pages: Vec<Vec<(String, String)>>, template: CreateEmbed) -> Vec<CreateEmbed> {
+    let total = pages.len();
+    pages.into_iter().enumerate().map(|(i, page)| {
+        template.clone()
+            .footer(CreateEmbedFooter::new(format!("Page {} / {total}", i + 1)))
+            .fields(page.into_iter().map(|(nom, valeur)| (nom, valeur, false)))
+    }).collect()
 }
 
-/// Tri un vecteur d’objets (avec leurs identifiants) par date, du plus récent au plus ancien.
-pub fn sort_by_date<'a, T: Object>(v: Vec<(&'a u64, &'a T)>) -> Vec<(&'a u64, &'a T)> {
-    if v.len() <= 1 {
-        v
-    } else {
-        _sort_merge(sort_by_date(v[..v.len() / 2].to_vec()), sort_by_date(v[v.len() / 2..].to_vec()))
+/// Tri un vecteur d’objets (avec leurs identifiants) en plaçant les objets épinglés
+/// ([`Object::is_pinned`]) en premier, puis par priorité décroissante ([`Object::get_priority`]),
+/// puis par date, du plus récent au plus ancien. Les objets n’étant par défaut pas épinglés et
+/// ayant tous la même priorité, le tri se fait uniquement par date.
+///
+/// À épinglage et priorité égaux, les objets sans date ([`Object::has_date`]) sont placés après
+/// les objets datés.
+///
+/// Le tri est stable : les objets de même priorité et de même date restent dans l’ordre où ils
+/// ont été donnés.
+pub fn sort_by_date<'a, T: Object>(mut v: Vec<(&'a u64, &'a T)>) -> Vec<(&'a u64, &'a T)> {
+    v.sort_by(|(_, a), (_, b)| b.is_pinned().cmp(&a.is_pinned())
+        .then_with(|| b.get_priority().cmp(&a.get_priority()))
+        .then_with(|| match (a.has_date(), b.has_date()) {
+            (true, true) => b.get_date().cmp(a.get_date()),
+            (a_date, b_date) => b_date.cmp(&a_date)
+        }));
+    v
+}
+
+/// Renvoie l’entrée de l’objet pour l’affichage dans une liste de résultats
+/// ([`Object::get_list_entry`]), dont le nom est rendu cliquable si l’objet a une URL
+/// ([`Object::get_url`]).
+///
+/// Seule la première occurrence du nom dans l’entrée est transformée en lien. L’entrée est
+/// renvoyée telle quelle si elle ne contient pas le nom ou contient déjà l’URL.
+pub fn list_entry<T: Object>(object: &T) -> String {
+    let entry = object.get_list_entry();
+    match object.get_url() {
+        Some(url) if !object.get_name().is_empty() && !entry.contains(&url) => {
+            let nom = object.get_name().replace('[', "\\[").replace(']', "\\]");
+            entry.replacen(object.get_name().as_str(), &format!("[{nom}]({url})"), 1)
+        },
+        _ => entry
     }
 }
 
@@ -238,36 +578,218 @@ pub fn create_paged_list<T, F: FnMut(&T) -> String>(mut objects: Vec<T>, mut str
 
 /// Récupère tous les messages d’un salon Discord depuis le messae indiqué (ou sa création si [`None`]). 
 /// Attention : cette commande
-/// peut prendre un certain temps à s’exécuter. Fonction récursive asynchrone renvoyant BoxFuture.
+/// peut prendre un certain temps à s’exécuter. Les messages sont renvoyés par paquets de 100, du
+/// paquet le plus ancien au plus récent.
+///
+/// Pour ne récupérer que les messages récents, voir [`get_channel_messages_limited`] et
+/// [`get_channel_messages_while`].
 pub fn get_channel_messages<'a>(chan: &'a GuildChannel, ctx: &'a SerenityContext, before: Option<&'a MessageId>) -> BoxFuture<'a, Result<Vec<Message>, ErrType>> {
     async move {
-        let mut get_messages = GetMessages::new().limit(100);
+        Ok(_fetch_messages(chan, ctx, before.copied(), None, |_| true).await?
+            .into_iter().rev().flatten().collect())
+    }.boxed()
+}
+
+/// Récupère au plus `max` messages d’un salon Discord (tous si [`None`]) avant le message indiqué
+/// (ou depuis le dernier message si [`None`]). Les messages sont renvoyés du plus récent au plus
+/// ancien.
+pub async fn get_channel_messages_limited(chan: &GuildChannel, ctx: &SerenityContext, before: Option<MessageId>, max: Option<usize>) -> Result<Vec<Message>, ErrType> {
+    get_channel_messages_while(chan, ctx, before, max, |_| true).await
+}
+
+/// Récupère les messages d’un salon Discord avant le message indiqué (ou depuis le dernier
+/// message si [`None`]), du plus récent au plus ancien, tant que la condition donnée est remplie.
+/// La récupération s’arrête au premier message ne remplissant pas la condition, qui n’est pas
+/// renvoyé, ou après `max` messages si une limite est donnée.
+///
+/// Exemple : `|message| *message.timestamp > date` ne récupère que les messages postérieurs à
+/// `date`.
+pub async fn get_channel_messages_while(chan: &GuildChannel, ctx: &SerenityContext, before: Option<MessageId>, max: Option<usize>, condition: impl FnMut(&Message) -> bool) -> Result<Vec<Message>, ErrType> {
+    Ok(_fetch_messages(chan, ctx, before, max, condition).await?.concat())
+}
+
+/* Récupère les messages par paquets de 100, du plus récent au plus ancien, jusqu’au début du
+   salon, jusqu’à `max` messages ou jusqu’au premier message ne remplissant pas la condition. */
+async fn _fetch_messages(chan: &GuildChannel, ctx: &SerenityContext, mut before: Option<MessageId>, max: Option<usize>, mut condition: impl FnMut(&Message) -> bool) -> Result<Vec<Vec<Message>>, ErrType> {
+    let mut paquets = Vec::new();
+    let mut restants = max.unwrap_or(usize::MAX);
+    while restants > 0 {
+        let mut get_messages = GetMessages::new().limit(restants.min(100) as u8);
         if let Some(before) = before {
             get_messages = get_messages.before(before);
         }
-        let current_messages = chan.messages(ctx, get_messages).await?;
-        Ok(vec![
-            if let Some(last_message) = current_messages.last() {
-                get_channel_messages(chan, ctx, Some(&last_message.id)).await?
-            } else {
-                vec![]
-            },
-            current_messages
-        ].concat())
-    }.boxed()
+        let mut paquet = chan.messages(ctx, get_messages).await?;
+        let complet = paquet.len() == restants.min(100);
+        let fin = paquet.iter().position(|message| !condition(message));
+        if let Some(fin) = fin {
+            paquet.truncate(fin);
+        }
+        before = paquet.last().map(|message| message.id);
+        restants -= paquet.len();
+        if !paquet.is_empty() {
+            paquets.push(paquet);
+        }
+        if fin.is_some() || !complet {
+            break;
+        }
+    }
+    Ok(paquets)
+}
+
+/// Renvoie l’URL `attachment://` permettant de référencer le fichier joint donné dans un embed
+/// du même message (voir [`Object::get_attachments`]).
+pub fn attachment_url(attachment: &CreateAttachment) -> String {
+    format!("attachment://{}", attachment.filename)
 }
 
+/// Crée l’embed de base d’un objet, de titre et de couleur donnés, dont le pied de page contient
+/// l’identifiant de l’objet sous la forme lue par défaut par [`Object::parse_footer_id`].
+///
+/// C’est la méthode recommandée pour implémenter [`Object::get_embed`] : il suffit d’y ajouter
+/// les champs de l’objet. Sans identifiant dans le pied de page, les messages des salons
+/// d’affichage ne sont pas reconnus à leur chargement et sont supprimés ; le pied de page ne
+/// doit donc pas être remplacé. Pour appliquer l’icône du thème du bot, voir
+/// [`crate::Theme::object_embed`].
+pub fn object_embed(id: u64, title: impl Into<String>, color: u32) -> CreateEmbed {
+    CreateEmbed::new()
+        .title(title)
+        .color(color)
+        .footer(CreateEmbedFooter::new(id.to_string()))
+}
+
+/// Préfixe des identifiants des boutons d’édition par formulaire créés par [`modal_button`]. Ces
+/// boutons ouvrent le formulaire de [`Object::modal`] et ne sont pas transmis à
+/// [`Object::buttons`].
+pub const MODAL_PREFIX: &str = "fondabots-modal-";
+
+/// Crée un bouton ouvrant le formulaire d’édition de l’objet d’identifiant donné (voir
+/// [`Object::modal`]), à placer dans [`Object::get_buttons`]. Le formulaire rempli est traité par
+/// [`Object::apply_modal`].
+pub fn modal_button(object_id: u64, label: impl Into<String>) -> CreateButton {
+    CreateButton::new(format!("{MODAL_PREFIX}{object_id}"))
+        .label(label)
+        .style(ButtonStyle::Secondary)
+}
+
+/// Préfixe des identifiants des boutons de navigation des messages à plusieurs pages (voir
+/// [`Bot::send_embed`]). Ces boutons sont traités par la bibliothèque et ne sont pas transmis à
+/// [`Object::buttons`].
+///
+/// Les messages envoyés par les versions précédentes utilisaient le préfixe `mm` suivi de
+/// chiffres (par exemple `mm1700000000000-n`) : ces identifiants restent réservés.
+pub const MULTIMESSAGE_PREFIX: &str = "fondabots-mm-";
+
+/* Vrai pour les identifiants des boutons de navigation des anciens multimessages : « mm »,
+ * chiffres, puis « -p » ou « -n ». */
+pub(crate) fn _is_legacy_multimessage_id(custom_id: &str) -> bool {
+    custom_id.strip_prefix("mm")
+        .and_then(|reste| reste.strip_suffix("-p").or_else(|| reste.strip_suffix("-n")))
+        .is_some_and(|horodatage| !horodatage.is_empty() && horodatage.chars().all(|c| c.is_ascii_digit()))
+}
+
+/// Renvoie `true` si l’identifiant de composant donné est réservé par la bibliothèque : boutons
+/// de navigation ([`MULTIMESSAGE_PREFIX`]) et de confirmation ([`CONFIRM_PREFIX`]). Un bouton de
+/// [`Object::get_buttons`] portant un tel identifiant n’est jamais transmis à [`Object::buttons`].
+///
+/// Les identifiants des boutons de [`modal_button`] ne sont pas concernés, ces boutons étant
+/// destinés à [`Object::get_buttons`].
+pub fn is_reserved_id(custom_id: &str) -> bool {
+    custom_id.starts_with(MULTIMESSAGE_PREFIX) || custom_id.starts_with(CONFIRM_PREFIX)
+        || _is_legacy_multimessage_id(custom_id)
+}
+
+/// Renvoie les identifiants réservés (voir [`is_reserved_id`]) utilisés par les boutons et menus
+/// de la rangée donnée, par exemple celle de [`Object::get_buttons`].
+pub fn reserved_ids(row: &CreateActionRow) -> Vec<String> {
+    let Some(row) = serenity::json::to_string(row).ok()
+        .and_then(|json| serenity::json::from_str::<ActionRow>(json).ok()) else {
+        return Vec::new();
+    };
+    row.components.into_iter().filter_map(|component| match component {
+        ActionRowComponent::Button(Button { data: ButtonKind::NonLink { custom_id, .. }, .. }) => Some(custom_id),
+        ActionRowComponent::SelectMenu(menu) => menu.custom_id,
+        _ => None
+    }).filter(|custom_id| is_reserved_id(custom_id)).collect()
+}
+
+/// Préfixe des identifiants des boutons de confirmation créés par [`confirm`]. Ces boutons
+/// sont traités directement par [`confirm`] et ne sont pas transmis à [`Object::buttons`].
+pub const CONFIRM_PREFIX: &str = "fondabots-confirm-";
+
+/// Demande une confirmation à l’auteur de la commande par deux boutons « Confirmer » et
+/// « Annuler ». Renvoie `true` si l’auteur a confirmé, `false` s’il a annulé ou n’a pas répondu
+/// dans le délai d’expiration des interactions (une minute par défaut, voir
+/// [`Bot::interaction_timeout`]).
+///
+/// <div class="warning">
+/// Le verrou sur les données du bot ne doit pas être tenu pendant l’appel, l’attente de la
+/// réponse pouvant durer jusqu’à l’expiration du délai.
+/// </div>
+pub async fn confirm<T: Object>(ctx: &Context<'_, DataType<T>, ErrType>, question: String) -> Result<bool, ErrType> {
+    let (confirmer, annuler, confirmee, annulee, delai, timeout) = {
+        let bot = ctx.data().lock().await;
+        (bot.tr("confirmation.confirmer", &[]), bot.tr("confirmation.annuler", &[]),
+         bot.tr("confirmation.confirmee", &[]), bot.tr("confirmation.annulee", &[]),
+         bot.tr("confirmation.delai", &[]), bot.get_interaction_timeout())
+    };
+    let confirm_id = format!("{CONFIRM_PREFIX}{}-oui", ctx.id());
+    let cancel_id = format!("{CONFIRM_PREFIX}{}-non", ctx.id());
+    let reply = ctx.send(CreateReply::default().content(question.clone()).components(vec![
+        CreateActionRow::Buttons(vec![
+            CreateButton::new(confirm_id.clone()).label(confirmer).style(ButtonStyle::Danger),
+            CreateButton::new(cancel_id.clone()).label(annuler).style(ButtonStyle::Secondary)
+        ])
+    ])).await?;
+    let message = reply.message().await?;
+
+    let interaction = ComponentInteractionCollector::new(ctx.serenity_context())
+        .author_id(ctx.author().id)
+        .message_id(message.id)
+        .filter(move |interaction| interaction.data.custom_id == confirm_id || interaction.data.custom_id == cancel_id)
+        .timeout(timeout)
+        .await;
+
+    let confirme = interaction.as_ref()
+        .is_some_and(|interaction| interaction.data.custom_id.ends_with("-oui"));
+    let conclusion = match (&interaction, confirme) {
+        (None, _) => delai,
+        (Some(_), true) => confirmee,
+        (Some(_), false) => annulee
+    };
+    match interaction {
+        Some(interaction) => interaction.create_response(ctx, CreateInteractionResponse::UpdateMessage(
+            CreateInteractionResponseMessage::new()
+                .content(format!("{question}\n{conclusion}"))
+                .components(vec![])
+        )).await?,
+        None => reply.edit(*ctx, CreateReply::default()
+            .content(format!("{question}\n{conclusion}"))
+            .components(vec![])).await?
+    }
+    Ok(confirme)
+}
+
+/// Vérifie que l’auteur de la commande a le rôle donné, et lui répond par un message d’erreur
+/// (voir [`Bot::tr`]) si ce n’est pas le cas. Verrouille les données du bot : si l’appelant tient
+/// déjà le verrou, utiliser [`check_for_role_with`].
 pub async fn check_for_role<T: Object>(ctx: &Context<'_, DataType<T>, ErrType>, role: RoleId) -> Result<bool, ErrType> {
+    let bot = ctx.data().lock().await;
+    check_for_role_with(ctx, &bot, role).await
+}
+
+/// Comme [`check_for_role`], mais le bot est donné en paramètre, comme pour [`get_object`] :
+/// l’appelant peut donc tenir le verrou sur ses données pendant l’appel.
+pub async fn check_for_role_with<T: Object>(ctx: &Context<'_, DataType<T>, ErrType>, bot: &Bot<T>, role: RoleId) -> Result<bool, ErrType> {
     let member = ctx.author_member().await;
     if let Some(member) = member {
         if !member.roles.contains(&role) {
-            ctx.reply("Vous n'avez pas l'autorisation d'utiliser cette commande.").await?;
+            ctx.reply(bot.tr("permission.refusee", &[])).await?;
             Ok(false)
         } else {
             Ok(true)
         }
     } else {
-        ctx.reply("Échec de la vérification de l'autorisation d'utiliser la commande. Réessayez plus tard.").await?;
+        ctx.reply(bot.tr("permission.echec", &[])).await?;
         Ok(false)
     }
 }
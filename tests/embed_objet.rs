@@ -0,0 +1,31 @@
+//! Embeds d’objets construits par `tools::object_embed` et `Theme::object_embed` : l’identifiant
+//! doit rester dans le pied de page pour le chargement des salons d’affichage.
+
+use fondabots_lib::tools::object_embed;
+use fondabots_lib::Theme;
+use poise::serenity_prelude::{CreateEmbed, Embed};
+
+fn relu(embed: CreateEmbed) -> Embed {
+    let json = poise::serenity_prelude::json::to_string(&embed).unwrap();
+    poise::serenity_prelude::json::from_str(json.as_str()).unwrap()
+}
+
+#[test]
+fn identifiant_dans_le_pied_de_page() {
+    let embed = relu(object_embed(42, "SCP-042", 0x123456).description("Description"));
+    assert_eq!(embed.footer.unwrap().text, "42");
+    assert_eq!(embed.title.as_deref(), Some("SCP-042"));
+    assert_eq!(embed.colour.map(|colour| colour.0), Some(0x123456));
+}
+
+#[test]
+fn theme_sans_remplacer_l_identifiant() {
+    let theme = Theme {
+        footer_text: Some("Fondation SCP".to_string()),
+        footer_icon: Some("https://example.com/logo.png".to_string()),
+        ..Theme::default()
+    };
+    let footer = relu(theme.object_embed(7, "Conte", theme.neutral_color)).footer.unwrap();
+    assert_eq!(footer.text, "7");
+    assert_eq!(footer.icon_url.as_deref(), Some("https://example.com/logo.png"));
+}
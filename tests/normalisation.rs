@@ -0,0 +1,47 @@
+//! Cas limites de la normalisation des chaînes de caractères (`tools::basicize`) utilisée par
+//! la recherche.
+
+use fondabots_lib::tools::basicize;
+
+#[test]
+fn minuscules_et_diacritiques() {
+    assert_eq!(basicize("Fondation"), "fondation");
+    assert_eq!(basicize("Éléphant à l’été"), "elephant a l'ete");
+    assert_eq!(basicize("ÇA"), "ca");
+}
+
+#[test]
+fn ligatures() {
+    assert_eq!(basicize("cœur"), "coeur");
+    assert_eq!(basicize("Œuvre"), "oeuvre");
+    assert_eq!(basicize("ex æquo"), "ex aequo");
+    assert_eq!(basicize("ﬁn"), "fin");
+}
+
+#[test]
+fn apostrophes_et_guillemets() {
+    assert_eq!(basicize("l’anomalie"), basicize("l'anomalie"));
+    assert_eq!(basicize("l‘anomalie"), "l'anomalie");
+    assert_eq!(basicize("l`anomalie"), "l'anomalie");
+    assert_eq!(basicize("“citation”"), "\"citation\"");
+}
+
+#[test]
+fn tirets() {
+    for tiret in ['\u{2010}', '\u{2011}', '\u{2012}', '–', '—', '\u{2015}', '\u{2212}'] {
+        assert_eq!(basicize(&format!("jean{tiret}pierre")), "jean-pierre");
+    }
+}
+
+#[test]
+fn espaces_insecables() {
+    for espace in ['\u{A0}', '\u{2009}', '\u{202F}'] {
+        assert_eq!(basicize(&format!("fondation{espace}scp")), "fondation scp");
+    }
+}
+
+#[test]
+fn caracteres_non_alphanumeriques_supprimes() {
+    assert_eq!(basicize("«\u{A0}titre\u{A0}»"), " titre ");
+    assert_eq!(basicize("☆étoile☆"), "etoile");
+}
@@ -0,0 +1,97 @@
+//! Recherche insensible à la casse, aux accents et aux variantes typographiques
+//! (`Bot::search`). Nécessite la feature `test-utils`.
+
+use fondabots_lib::object::Object;
+use fondabots_lib::testing::bot_with;
+use fondabots_lib::{Bot, DataType, ErrType};
+use poise::serenity_prelude::{ComponentInteraction, Context, CreateActionRow, CreateEmbed};
+use yaml_rust2::Yaml;
+
+#[derive(Clone, Debug, PartialEq)]
+struct Ecrit {
+    id: u64,
+    nom: String
+}
+
+impl Object for Ecrit {
+    fn get_id(&self) -> u64 { self.id }
+    fn from_yaml(_data: &Yaml) -> Result<Self, ErrType> { unimplemented!() }
+    fn serialize(&self) -> Yaml { Yaml::Null }
+    fn is_modified(&self) -> bool { false }
+    fn set_modified(&mut self, _modified: bool) {}
+    fn get_embed(&self) -> CreateEmbed { CreateEmbed::new() }
+    fn get_buttons(&self) -> CreateActionRow { CreateActionRow::Buttons(Vec::new()) }
+    fn get_name(&self) -> &String { &self.nom }
+    fn set_name(&mut self, s: String) { self.nom = s; }
+    fn get_list_entry(&self) -> String { self.nom.clone() }
+    fn up(&mut self) {}
+    async fn buttons(_ctx: &Context, _interaction: &mut ComponentInteraction, _bot: &mut Bot<Self>) -> Result<(), ErrType> { Ok(()) }
+    async fn maj_rss(_bot: &DataType<Self>) -> Result<(), ErrType> { Ok(()) }
+}
+
+fn bot(noms: &[&str]) -> Bot<Ecrit> {
+    bot_with(noms.iter().enumerate().map(|(id, nom)| Ecrit { id: id as u64, nom: nom.to_string() }))
+}
+
+fn ids(bot: &Bot<Ecrit>, critere: &str) -> Vec<u64> {
+    let mut ids: Vec<u64> = bot.search(critere).into_iter().copied().collect();
+    ids.sort();
+    ids
+}
+
+#[test]
+fn casse_et_accents() {
+    let bot = bot(&["La Fondation", "Éléphant"]);
+    assert_eq!(ids(&bot, "fondation"), [0]);
+    assert_eq!(ids(&bot, "FONDATION"), [0]);
+    assert_eq!(ids(&bot, "elephant"), [1]);
+    assert_eq!(ids(&bot, "éléphant"), [1]);
+}
+
+#[test]
+fn apostrophe_courbe() {
+    let bot = bot(&["L’Anomalie du phare", "L'Autre anomalie"]);
+    assert_eq!(ids(&bot, "l'anomalie"), [0]);
+    assert_eq!(ids(&bot, "l’autre"), [1]);
+    assert_eq!(ids(&bot, "anomalie"), [0, 1]);
+}
+
+#[test]
+fn ligatures() {
+    let bot = bot(&["Le Cœur de la bête", "Coeur brisé"]);
+    assert_eq!(ids(&bot, "coeur"), [0, 1]);
+    assert_eq!(ids(&bot, "cœur"), [0, 1]);
+}
+
+#[test]
+fn tirets() {
+    let bot = bot(&["Jean–Pierre", "SCP‑173"]);
+    assert_eq!(ids(&bot, "jean-pierre"), [0]);
+    assert_eq!(ids(&bot, "scp-173"), [1]);
+}
+
+#[test]
+fn espaces_insecables() {
+    let bot = bot(&["Fondation\u{A0}SCP", "Rapport\u{202F}:\u{202F}incident"]);
+    assert_eq!(ids(&bot, "scp"), [0]);
+    assert_eq!(ids(&bot, "fondation\u{A0}scp"), [0]);
+    assert_eq!(ids(&bot, "rapport incident"), [1]);
+    assert_eq!(bot.search_scored("fondation scp").first().map(|(id, _)| *id), Some(0));
+}
+
+#[test]
+fn exclusion() {
+    let bot = bot(&["Cœur de pierre", "Cœur de verre"]);
+    assert_eq!(ids(&bot, "coeur -verre"), [0]);
+}
+
+#[test]
+fn pagination() {
+    let bot = bot(&["Rapport", "Rapport d’incident", "Ancien rapport", "Rapports", "Fondation"]);
+    let tous = bot.search_scored("rapport");
+    assert_eq!(tous.len(), 4);
+    assert_eq!(bot.search_paged("rapport", 0, 2), tous[..2]);
+    assert_eq!(bot.search_paged("rapport", 2, 10), tous[2..]);
+    assert!(bot.search_paged("rapport", 4, 2).is_empty());
+    assert!(bot.search_paged("rapport", 0, 0).is_empty());
+}
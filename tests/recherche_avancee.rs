@@ -0,0 +1,63 @@
+//! Lecture des requêtes de recherche avancée (`tools::parse_advanced_query`).
+
+use chrono::{FixedOffset, TimeZone, Utc};
+use fondabots_lib::tools::{parse_advanced_query, parse_advanced_query_with, AdvancedQuery, Timezone};
+use poise::serenity_prelude::Timestamp;
+
+fn minuit_utc(annee: i32, mois: u32, jour: u32) -> Timestamp {
+    Timestamp::from(Utc.with_ymd_and_hms(annee, mois, jour, 0, 0, 0).unwrap())
+}
+
+#[test]
+fn mots_du_nom() {
+    let query = parse_advanced_query("la  fondation nom:phare").unwrap();
+    assert_eq!(query, AdvancedQuery { nom: "la fondation phare".to_string(), ..Default::default() });
+    assert_eq!(parse_advanced_query("").unwrap(), AdvancedQuery::default());
+}
+
+#[test]
+fn valeurs_entre_guillemets() {
+    let query = parse_advanced_query(r#"nom:"la fondation" etat:"en attente""#).unwrap();
+    assert_eq!(query.nom, "la fondation");
+    assert_eq!(query.filtres, [("etat".to_string(), "en attente".to_string())]);
+    assert_eq!(parse_advanced_query(r#"nom:"la fondation"#), Err(r#"nom:"la fondation"#.to_string()));
+}
+
+#[test]
+fn apres_avec_ou_sans_accent() {
+    let sans_accent = parse_advanced_query("apres:01/02/2024").unwrap();
+    let avec_accent = parse_advanced_query("après:01/02/2024").unwrap();
+    assert_eq!(sans_accent.apres, Some(minuit_utc(2024, 2, 1)));
+    assert_eq!(sans_accent, avec_accent);
+    assert_eq!(parse_advanced_query("avant:31/12/2023").unwrap().avant, Some(minuit_utc(2023, 12, 31)));
+}
+
+#[test]
+fn date_invalide() {
+    assert_eq!(parse_advanced_query("avant:2024-01-01"), Err("avant:2024-01-01".to_string()));
+    assert_eq!(parse_advanced_query("phare apres:31/02/2024"), Err("apres:31/02/2024".to_string()));
+    assert_eq!(parse_advanced_query("après:"), Err("après:".to_string()));
+}
+
+#[test]
+fn cle_ou_valeur_vide() {
+    assert_eq!(parse_advanced_query(":attente"), Err(":attente".to_string()));
+    assert_eq!(parse_advanced_query("etat:"), Err("etat:".to_string()));
+}
+
+#[test]
+fn cles_inconnues_en_filtres() {
+    let query = parse_advanced_query("phare etat:attente auteur:Dr_Bright").unwrap();
+    assert_eq!(query.nom, "phare");
+    assert_eq!(query.filtres, [
+        ("etat".to_string(), "attente".to_string()),
+        ("auteur".to_string(), "Dr_Bright".to_string())
+    ]);
+}
+
+#[test]
+fn fuseau_horaire() {
+    let utc_plus_un = Timezone::new(FixedOffset::east_opt(3600).unwrap());
+    let query = parse_advanced_query_with("avant:01/01/2024", &utc_plus_un).unwrap();
+    assert_eq!(query.avant, Some(Timestamp::from(Utc.with_ymd_and_hms(2023, 12, 31, 23, 0, 0).unwrap())));
+}